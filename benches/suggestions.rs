@@ -1,3 +1,6 @@
+// The benches need a newer toolchain than the MSRV anyway, for criterion
+#![allow(clippy::incompatible_msrv)]
+
 use std::{collections::HashMap, hint::black_box};
 
 use ahash::RandomState;
@@ -19,6 +22,30 @@ fn upodesh_avro_benchmark(c: &mut Criterion) {
     c.bench_function("upodesh avro bistari", |b| {
         b.iter(|| suggest.suggest(black_box("bistari")))
     });
+    c.bench_function("upodesh avro top 9 a", |b| {
+        b.iter(|| suggest.suggest_top_k(black_box("a"), 9))
+    });
+    c.bench_function("upodesh avro top 9 bistari", |b| {
+        b.iter(|| suggest.suggest_top_k(black_box("bistari"), 9))
+    });
+
+    // The last keystroke of a long input, against suggesting for the whole input
+    c.bench_function("upodesh avro ongshochched", |b| {
+        b.iter(|| suggest.suggest(black_box("ongshochched")))
+    });
+    c.bench_function("upodesh avro session ongshochched", |b| {
+        let mut session = suggest.session();
+        for c in "ongshochche".chars() {
+            session.push_char(c);
+        }
+
+        b.iter(|| {
+            session.push_char(black_box('d'));
+            let suggestions = session.suggestions();
+            session.pop_char();
+            suggestions
+        })
+    });
 }
 
 fn regex_avro_benchmark(c: &mut Criterion) {
@@ -85,7 +112,7 @@ fn regex_avro_benchmark(c: &mut Criterion) {
 }
 
 fn upodesh_bangla_benchmark(c: &mut Criterion) {
-    use upodesh::bangla::suggest;
+    use upodesh::bangla::{suggest, suggest_fuzzy, suggest_top_k};
     c.bench_function("upodesh bangla আমা", |b| {
         b.iter(|| suggest(black_box("আমা")))
     });
@@ -95,6 +122,12 @@ fn upodesh_bangla_benchmark(c: &mut Criterion) {
     c.bench_function("upodesh bangla কনট্রো", |b| {
         b.iter(|| suggest(black_box("কনট্রো")))
     });
+    c.bench_function("upodesh bangla top 9 কম্পি", |b| {
+        b.iter(|| suggest_top_k(black_box("কম্পি"), 9))
+    });
+    c.bench_function("upodesh bangla fuzzy 1 কম্পিউটর", |b| {
+        b.iter(|| suggest_fuzzy(black_box("কম্পিউটর"), 1))
+    });
 }
 
 fn regex_bangla_benchmark(c: &mut Criterion) {
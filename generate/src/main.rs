@@ -3,13 +3,23 @@ use std::{
     env::{args, var_os},
     fs::{File, read, read_to_string},
     io::BufWriter,
-    path::PathBuf,
+    path::{Path, PathBuf},
 };
 
 use fst::raw::Builder;
 use rexplode::explode;
 use serde::{Deserialize, Serialize};
 
+// Shared with the library, so that both normalize and encode the words the same way.
+#[allow(dead_code)]
+#[path = "../../src/encoding.rs"]
+mod encoding;
+#[path = "../../src/normalization.rs"]
+mod normalization;
+
+/// Must match `MAX_WEIGHT` in `src/fst.rs`.
+const MAX_WEIGHT: u64 = u32::MAX as u64;
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Block {
@@ -25,57 +35,57 @@ pub struct RegexBlock {
     pub entire_block_optional: Option<bool>,
 }
 
-fn generate_words_fst() {
+fn generate_words_fsts() {
     let root = PathBuf::from(var_os("CARGO_MANIFEST_DIR").unwrap());
     let parent = root.parent().unwrap();
-    let dest = parent.join("src").join("words.fst");
-
-    let file = File::create(dest).expect("Failed to create words.fst");
-    let writer = BufWriter::new(file);
-
-    let mut fst = Builder::new(writer).unwrap();
-    let words = read_to_string(parent.join("data/source-words.txt"))
-        .expect("Failed to read source words file");
-
-    let mut words = words.lines().map(str::trim).collect::<Vec<_>>();
-
-    words.sort();
 
-    for word in words {
-        let numbered_word = word.chars().map(|c| c as u8).collect::<Vec<u8>>();
-        fst.add(&numbered_word).expect("Failed to add word to FST");
-    }
-
-    fst.finish().expect("Failed to finish words FST generation");
+    generate_fst(
+        &parent.join("data/source-words.txt"),
+        &parent.join("src/words.fst"),
+    );
+    generate_fst(
+        &parent.join("data/source-english-words.txt"),
+        &parent.join("src/english.fst"),
+    );
 }
 
-fn generate_patterns_fst() {
-    let root = PathBuf::from(var_os("CARGO_MANIFEST_DIR").unwrap());
-    let parent = root.parent().unwrap();
-    let dest = parent.join("src").join("avro").join("patterns.fst");
-
-    let file = File::create(dest).expect("Failed to create patterns.fst");
+fn generate_fst(source: &Path, dest: &Path) {
+    let file = File::create(dest).unwrap_or_else(|_| panic!("Failed to create {dest:?}"));
     let writer = BufWriter::new(file);
 
     let mut fst = Builder::new(writer).unwrap();
-    let patterns: HashMap<String, Block> = serde_json::from_slice(
-        &read(parent.join("data/preprocessed-patterns.json"))
-            .expect("Failed to read source patterns file"),
-    )
-    .unwrap();
-
-    let mut patterns = patterns.keys().map(|s| s.as_str()).collect::<Vec<_>>();
-
-    patterns.sort();
+    let words = read_to_string(source).unwrap_or_else(|_| panic!("Failed to read {source:?}"));
+
+    // Each line is a word, optionally followed by a tab and its weight (frequency).
+    let mut weights: BTreeMap<Vec<u8>, u64> = BTreeMap::new();
+
+    for line in words.lines() {
+        let (word, weight) = match line.split_once('\t') {
+            Some((word, weight)) => (
+                word.trim(),
+                weight.trim().parse::<u64>().expect("Invalid word weight"),
+            ),
+            None => (line.trim(), 0),
+        };
+
+        if word.is_empty() {
+            continue;
+        }
+
+        let word = &normalization::normalize(word);
+        let numbered_word = encoding::encode_str(word)
+            .unwrap_or_else(|| panic!("Unsupported character in the word {word:?}"));
+        let entry = weights.entry(numbered_word).or_default();
+        *entry = (*entry).max(weight.min(MAX_WEIGHT));
+    }
 
-    for pattern in patterns {
-        let numbered_word = pattern.chars().map(|c| c as u8).collect::<Vec<u8>>();
-        fst.add(&numbered_word)
-            .expect("Failed to add pattern to FST");
+    for (numbered_word, weight) in weights {
+        // Store the cost, so that the output of a prefix is the cost of the best word below it.
+        fst.insert(&numbered_word, MAX_WEIGHT - weight)
+            .expect("Failed to add word to FST");
     }
 
-    fst.finish()
-        .expect("Failed to finish patterns FST generation");
+    fst.finish().expect("Failed to finish FST generation");
 }
 
 fn generate_regex_exploded_patterns(source: &str, dest: &str) {
@@ -128,7 +138,6 @@ fn main() {
 
         generate_regex_exploded_patterns(source, dest);
     } else {
-        generate_words_fst();
-        generate_patterns_fst();
+        generate_words_fsts();
     }
 }
@@ -0,0 +1,110 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    ops::Bound,
+};
+
+use serde::Deserialize;
+
+use crate::{avro::utils::fix_string, normalization::normalize};
+
+/// The highest number of entries suggested for the inputs they start with.
+const PREFIX_LIMIT: usize = 3;
+
+/// The length of the shortest input for which the entries starting with it
+/// are suggested, as shorter ones would match too many entries.
+const MIN_PREFIX_LEN: usize = 3;
+
+/// The fixed Bangla words of the Avro inputs, in the JSON format of
+/// `autocorrect.json`.
+#[derive(Default, Deserialize)]
+#[serde(from = "HashMap<String, String>")]
+pub(crate) struct Autocorrect {
+    /// The words by their inputs, as normalized by `fix_string()`.
+    entries: BTreeMap<String, String>,
+}
+
+impl From<HashMap<String, String>> for Autocorrect {
+    fn from(entries: HashMap<String, String>) -> Self {
+        let entries = entries
+            .into_iter()
+            .map(|(input, word)| (fix_string(&input), normalize(word.trim())))
+            .filter(|(input, word)| !input.is_empty() && !word.is_empty())
+            .collect();
+
+        Autocorrect { entries }
+    }
+}
+
+impl Autocorrect {
+    /// Returns the word of the normalized `input`, if any.
+    pub fn word(&self, input: &str) -> Option<&str> {
+        self.entries.get(input).map(String::as_str)
+    }
+
+    /// Returns the words of up to three longer inputs starting with the
+    /// normalized `input`, the shortest first, if it is long enough.
+    pub fn completions(&self, input: &str) -> Vec<&str> {
+        let mut words: Vec<&str> = Vec::new();
+
+        if input.len() < MIN_PREFIX_LEN {
+            return words;
+        }
+
+        let mut prefixed: Vec<_> = self
+            .entries
+            .range::<str, _>((Bound::Excluded(input), Bound::Unbounded))
+            .take_while(|(key, _)| key.starts_with(input))
+            .collect();
+        // The sort is stable, so the inputs of the same length stay sorted
+        prefixed.sort_by_key(|(key, _)| key.len());
+
+        let word = self.word(input);
+        for (_, completion) in prefixed {
+            if words.len() == PREFIX_LIMIT {
+                break;
+            }
+            if word != Some(completion.as_str()) && !words.contains(&completion.as_str()) {
+                words.push(completion);
+            }
+        }
+
+        words
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_autocorrect() {
+        let autocorrect: Autocorrect = serde_json::from_str(
+            r#"{ "ami": "আমি", "bangla": "বাংলা", "bangladesh": "বাংলাদেশ",
+                "banglalink": "বাংলালিংক", "banglay": "বাংলায়", "bangali": "বাঙালি",
+                "obak": "অবাক", "Empty": " " }"#,
+        )
+        .unwrap();
+
+        assert_eq!(autocorrect.word("ami"), Some("আমি"));
+        assert!(autocorrect.completions("ami").is_empty());
+        assert_eq!(autocorrect.word("bangla"), Some("বাংলা"));
+        assert_eq!(
+            autocorrect.completions("bangla"),
+            [
+                "\u{09AC}\u{09BE}\u{0982}\u{09B2}\u{09BE}\u{09DF}",
+                "বাংলাদেশ",
+                "বাংলালিংক"
+            ]
+        );
+        assert_eq!(autocorrect.word("banglad"), None);
+        assert_eq!(autocorrect.completions("banglad"), ["বাংলাদেশ"]);
+        assert_eq!(autocorrect.completions("bangl").len(), 3);
+        // The inputs of the entries are normalized like the Avro input
+        assert_eq!(autocorrect.word("Obak"), Some("অবাক"));
+        // Too short for the prefixes
+        assert!(autocorrect.completions("ba").is_empty());
+        assert_eq!(autocorrect.word("empty"), None);
+        assert_eq!(autocorrect.word(""), None);
+        assert!(autocorrect.completions("").is_empty());
+    }
+}
@@ -0,0 +1,333 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::Path,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{avro::utils::fix_string, Error};
+
+/// The number of selections after which the score of a previous selection is halved.
+const HALF_LIFE: f64 = 500.0;
+
+/// Scores below this are forgotten.
+const MIN_SCORE: f64 = 0.01;
+
+/// The share of its score a selection gives to the inputs one edit away from
+/// its own input.
+const SIMILAR_SHARE: f64 = 0.5;
+
+/// The length of the shortest input for which the selections of the inputs
+/// one edit away count, as shorter ones are similar to too many inputs.
+const MIN_SIMILAR_LEN: usize = 3;
+
+/// The words the user selected for their Avro inputs.
+///
+/// Every selection adds `1` to the score of the word for the input, and the
+/// scores decay as more selections are made, so that recent choices matter
+/// more. Inputs are compared after normalization, so `ami` and `Ami,` share
+/// their selections. The selections also count at half their score for the
+/// inputs one inserted, removed or replaced character away, like `amii`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(from = "SavedHistory")]
+pub struct SelectionHistory {
+    /// The total number of selections recorded, used as the clock for decaying.
+    selections: u64,
+    inputs: HashMap<String, HashMap<String, Selection>>,
+    /// The inputs by their variants with a character removed, and by
+    /// themselves, for finding the similar inputs without comparing them all.
+    #[serde(skip)]
+    variants: HashMap<String, Vec<String>>,
+}
+
+/// The saved form of a [`SelectionHistory`], without the variants of the
+/// inputs.
+#[derive(Deserialize)]
+struct SavedHistory {
+    selections: u64,
+    inputs: HashMap<String, HashMap<String, Selection>>,
+}
+
+impl From<SavedHistory> for SelectionHistory {
+    fn from(saved: SavedHistory) -> Self {
+        let mut history = SelectionHistory {
+            selections: saved.selections,
+            inputs: saved.inputs,
+            variants: HashMap::new(),
+        };
+        history.index_variants();
+        history
+    }
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+struct Selection {
+    score: f64,
+    /// The value of the clock when the score was last updated.
+    at: u64,
+}
+
+impl Selection {
+    fn score_at(&self, now: u64) -> f64 {
+        // A hand-edited or mismatched history may be ahead of the clock
+        self.score * 0.5f64.powf(now.saturating_sub(self.at) as f64 / HALF_LIFE)
+    }
+}
+
+impl SelectionHistory {
+    /// Creates an empty selection history.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads the selection history saved in the file at `path`.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        serde_json::from_slice(&fs::read(path)?).map_err(Error::InvalidSelectionHistory)
+    }
+
+    /// Saves the selection history to the file at `path`.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        let json = serde_json::to_vec(self).map_err(Error::InvalidSelectionHistory)?;
+        Ok(fs::write(path, json)?)
+    }
+
+    /// Records that the user selected the `word` for the Avro `input`.
+    pub fn record(&mut self, input: &str, word: &str) {
+        let input = fix_string(input);
+
+        if input.is_empty() || word.is_empty() {
+            return;
+        }
+
+        self.selections += 1;
+        let now = self.selections;
+
+        if !self.inputs.contains_key(&input) {
+            for variant in variants(&input) {
+                self.variants
+                    .entry(variant)
+                    .or_default()
+                    .push(input.clone());
+            }
+        }
+
+        let selection = self
+            .inputs
+            .entry(input)
+            .or_default()
+            .entry(word.to_string())
+            .or_insert(Selection {
+                score: 0.0,
+                at: now,
+            });
+        selection.score = selection.score_at(now) + 1.0;
+        selection.at = now;
+
+        // Forget the selections which decayed away, once in a while
+        if now % HALF_LIFE as u64 == 0 {
+            self.inputs.retain(|_, words| {
+                words.retain(|_, s| s.score_at(now) >= MIN_SCORE);
+                !words.is_empty()
+            });
+            self.index_variants();
+        }
+    }
+
+    /// Rebuilds the index of the inputs by their variants.
+    fn index_variants(&mut self) {
+        self.variants.clear();
+
+        for input in self.inputs.keys() {
+            for variant in variants(input) {
+                self.variants
+                    .entry(variant)
+                    .or_default()
+                    .push(input.clone());
+            }
+        }
+    }
+
+    /// Returns the decayed selection score of the `word` for the Avro `input`,
+    /// or for the similar inputs.
+    pub fn score(&self, input: &str, word: &str) -> f64 {
+        self.scores(&fix_string(input))
+            .get(word)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Returns whether no selection has been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.inputs.is_empty()
+    }
+
+    /// Returns the words selected for the normalized `input` or the similar
+    /// inputs with their scores, the highest one for the words selected for
+    /// several of them.
+    pub(crate) fn scores(&self, input: &str) -> HashMap<&str, f64> {
+        let now = self.selections;
+        let mut scores = HashMap::new();
+
+        if self.inputs.is_empty() {
+            return scores;
+        }
+
+        // The inputs one edit away share a variant with the `input`
+        let others: HashSet<&str> = variants(input)
+            .filter_map(|variant| self.variants.get(&variant))
+            .flatten()
+            .map(String::as_str)
+            .filter(|other| is_similar(input, other))
+            .collect();
+        let similar = others
+            .into_iter()
+            .filter_map(|other| self.inputs.get(other))
+            .map(|words| (words, SIMILAR_SHARE));
+        let inputs = self.inputs.get(input).map(|words| (words, 1.0));
+
+        for (words, share) in inputs.into_iter().chain(similar) {
+            for (word, selection) in words {
+                let score = selection.score_at(now) * share;
+                if score >= MIN_SCORE {
+                    let best: &mut f64 = scores.entry(word.as_str()).or_default();
+                    *best = best.max(score);
+                }
+            }
+        }
+
+        scores
+    }
+}
+
+/// Returns the `input` and its variants with a character removed, if it is
+/// long enough to have similar inputs.
+fn variants(input: &str) -> impl Iterator<Item = String> + '_ {
+    let long_enough = input.chars().count() >= MIN_SIMILAR_LEN;
+    let removed = input.char_indices().map(move |(i, c)| {
+        let mut variant = input.to_string();
+        variant.replace_range(i..i + c.len_utf8(), "");
+        variant
+    });
+
+    long_enough
+        .then(|| std::iter::once(input.to_string()).chain(removed))
+        .into_iter()
+        .flatten()
+}
+
+/// Whether the normalized inputs `a` and `b` are one inserted, removed or
+/// replaced character away from each other, and long enough to be similar.
+fn is_similar(a: &str, b: &str) -> bool {
+    let (a_len, b_len) = (a.chars().count(), b.chars().count());
+    let longer = a_len.max(b_len);
+
+    if a == b || a_len.min(b_len) < MIN_SIMILAR_LEN || longer - a_len.min(b_len) > 1 {
+        return false;
+    }
+
+    // The characters around the edit are the same
+    let prefix = a.chars().zip(b.chars()).take_while(|(x, y)| x == y).count();
+    let suffix = a
+        .chars()
+        .rev()
+        .zip(b.chars().rev())
+        .take_while(|(x, y)| x == y)
+        .count();
+
+    prefix + suffix >= longer - 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_selection_history() {
+        let mut history = SelectionHistory::new();
+        assert!(history.is_empty());
+        assert_eq!(history.score("ami", "আমি"), 0.0);
+
+        history.record("ami", "আমি");
+        assert_eq!(history.score("ami", "আমি"), 1.0);
+        assert_eq!(history.score("Ami,", "আমি"), 1.0);
+        assert_eq!(history.score("amii", "আমি"), 0.5);
+        assert_eq!(history.score("amiii", "আমি"), 0.0);
+
+        history.record("ami", "আমি");
+        history.record("ami", "আমী");
+        assert!(history.score("ami", "আমি") > history.score("ami", "আমী"));
+
+        // Old selections decay
+        for _ in 0..HALF_LIFE as usize {
+            history.record("tumi", "তুমি");
+        }
+        let score = history.score("ami", "আমী");
+        assert!(score < 0.51 && score > 0.49);
+
+        // The similar inputs get half the score
+        assert_eq!(history.score("amu", "আমী"), score / 2.0);
+        assert_eq!(history.score("am", "আমী"), 0.0);
+
+        history.record("", "আমি");
+        history.record("ami", "");
+        assert_eq!(history.score("", "আমি"), 0.0);
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let mut history = SelectionHistory::new();
+        history.record("ami", "আমি");
+        history.record("tumi", "তুমি");
+
+        let path = std::env::temp_dir().join(format!(
+            "upodesh-selection-history-test-{}.json",
+            std::process::id()
+        ));
+        history.save(&path).unwrap();
+        let loaded = SelectionHistory::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.score("ami", "আমি"), history.score("ami", "আমি"));
+        assert_eq!(loaded.score("tumi", "তুমি"), 1.0);
+        // The similar inputs are found again
+        assert_eq!(loaded.score("tumii", "তুমি"), 0.5);
+
+        fs::write(&path, r#"{ "selections": "1" }"#).unwrap();
+        let error = SelectionHistory::load(&path).unwrap_err();
+        fs::remove_file(&path).unwrap();
+        assert!(matches!(error, Error::InvalidSelectionHistory(_)));
+        assert!(matches!(SelectionHistory::load(&path), Err(Error::Io(_))));
+    }
+
+    #[test]
+    fn test_similar_inputs() {
+        assert!(is_similar("ami", "amii"));
+        assert!(is_similar("amii", "ami"));
+        assert!(is_similar("ami", "aki"));
+        assert!(is_similar("bangla", "bngla"));
+        assert!(!is_similar("ami", "ami"));
+        assert!(!is_similar("ami", "ima"));
+        assert!(!is_similar("ami", "amiii"));
+        assert!(!is_similar("am", "ami"));
+    }
+
+    #[test]
+    fn test_history_ahead_of_the_clock() {
+        let path = std::env::temp_dir().join(format!(
+            "upodesh-history-ahead-test-{}.json",
+            std::process::id()
+        ));
+        fs::write(
+            &path,
+            r#"{ "selections": 1, "inputs": { "ami": { "আমি": { "score": 2.0, "at": 10 } } } }"#,
+        )
+        .unwrap();
+        let mut history = SelectionHistory::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(history.score("ami", "আমি"), 2.0);
+        history.record("ami", "আমি");
+        assert_eq!(history.score("ami", "আমি"), 3.0);
+    }
+}
@@ -1,3 +1,29 @@
+mod autocorrect;
+mod history;
+mod patterns;
+mod phonetic;
+mod session;
 mod suggest;
 mod utils;
-pub use suggest::Suggest;
+pub use history::SelectionHistory;
+pub use session::Session;
+pub use suggest::{Block, Suggest, SuggestBuilder, Unmatched};
+
+/// A suggester with the embedded data, for the functions not depending on
+/// the user's data.
+#[cfg(feature = "embedded")]
+static SUGGEST: once_cell::sync::Lazy<Suggest> = once_cell::sync::Lazy::new(Suggest::new);
+
+/// Returns the Avro inputs for which the Bangla `word` is suggested, with
+/// the embedded pattern tables. See [`Suggest::reverse`].
+#[cfg(feature = "embedded")]
+pub fn reverse(word: &str) -> Vec<String> {
+    SUGGEST.reverse(word)
+}
+
+/// Converts the Avro `input` into Bangla with the embedded Avro Phonetic
+/// rules. See [`Suggest::convert`].
+#[cfg(feature = "embedded")]
+pub fn convert(input: &str) -> String {
+    SUGGEST.convert(input)
+}
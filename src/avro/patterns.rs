@@ -0,0 +1,438 @@
+use std::{collections::HashMap, iter::Peekable, ops::Range, str::CharIndices};
+
+use serde::Deserialize;
+
+use crate::{avro::suggest::Block, Error};
+
+/// The most texts a regex or any part of it may expand into.
+const MAX_TEXTS: usize = 1 << 16;
+
+/// The highest bound of a repetition like `{1,3}`.
+const MAX_REPETITION: u32 = 64;
+
+/// The pattern table in the JSON format of `source-regex-patterns.json`.
+pub(super) type RegexPatterns = HashMap<String, RegexBlock>;
+
+/// The changes to a pattern table, in the JSON format of
+/// `source-regex-patterns.json`. A `null` block removes the pattern.
+pub(super) type Overrides = HashMap<String, Option<RegexBlock>>;
+
+/// A pattern block whose transliterations are written as a regex.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct RegexBlock {
+    transliterate: String,
+    entire_block_optional: Option<bool>,
+}
+
+impl RegexBlock {
+    /// Expands the regex of the pattern `key` into the transliterations of
+    /// its block.
+    pub fn into_block(self, key: &str, expander: &mut Expander) -> Result<Block, Error> {
+        match expander.expand(&self.transliterate) {
+            Ok(transliterate) => Ok(Block {
+                transliterate,
+                entire_block_optional: self.entire_block_optional,
+            }),
+            Err(reason) => Err(Error::UnsupportedRegex {
+                key: key.to_string(),
+                regex: self.transliterate,
+                reason,
+            }),
+        }
+    }
+}
+
+/// Expands the regexes of the patterns into all the texts they match, like
+/// the `generate explode` tool.
+///
+/// The expansions of the groups are kept, as the patterns share many of
+/// them, like the vowel signs.
+#[derive(Default)]
+pub(super) struct Expander {
+    cache: HashMap<String, Vec<String>>,
+}
+
+impl Expander {
+    /// Returns the texts matched by the `regex`, sorted and without
+    /// duplicates, or the reason it can't be expanded.
+    ///
+    /// Only the literals, groups, alternations, bracketed classes and bounded
+    /// repetitions like `?` or `{1,2}` are supported, as the others don't
+    /// match a finite set of texts.
+    pub fn expand(&mut self, regex: &str) -> Result<Vec<String>, String> {
+        let node = Parser::new(regex).parse()?;
+        let mut texts = self.texts(regex, &node)?;
+
+        texts.sort();
+        texts.dedup();
+        Ok(texts)
+    }
+
+    fn texts(&mut self, regex: &str, node: &Node) -> Result<Vec<String>, String> {
+        let texts = match node {
+            Node::Chars(chars) => chars.iter().map(char::to_string).collect(),
+            Node::Concat(nodes) => {
+                let mut texts = vec![String::new()];
+                for node in nodes {
+                    texts = product(&texts, &self.texts(regex, node)?)?;
+                }
+                texts
+            }
+            Node::Alternation(nodes) => {
+                let mut texts = Vec::new();
+                for node in nodes {
+                    texts.extend(self.texts(regex, node)?);
+                }
+                texts
+            }
+            Node::Group(node, span) => {
+                if let Some(texts) = self.cache.get(&regex[span.clone()]) {
+                    return Ok(texts.clone());
+                }
+                let texts = self.texts(regex, node)?;
+                self.cache
+                    .insert(regex[span.clone()].to_string(), texts.clone());
+                texts
+            }
+            Node::Repetition(node, min, max) => {
+                let once = self.texts(regex, node)?;
+                let mut texts = Vec::new();
+                let mut repeated = vec![String::new()];
+
+                for count in 0..=*max {
+                    if count >= *min {
+                        texts.extend(repeated.iter().cloned());
+                    }
+                    if count < *max {
+                        repeated = product(&repeated, &once)?;
+                    }
+                }
+                texts
+            }
+        };
+
+        if texts.len() > MAX_TEXTS {
+            return Err(format!("it matches more than {MAX_TEXTS} texts"));
+        }
+        Ok(texts)
+    }
+}
+
+/// Appends each of the `suffixes` to each of the `prefixes`.
+fn product(prefixes: &[String], suffixes: &[String]) -> Result<Vec<String>, String> {
+    if prefixes.len().saturating_mul(suffixes.len()) > MAX_TEXTS {
+        return Err(format!("it matches more than {MAX_TEXTS} texts"));
+    }
+
+    Ok(prefixes
+        .iter()
+        .flat_map(|prefix| {
+            suffixes
+                .iter()
+                .map(move |suffix| format!("{prefix}{suffix}"))
+        })
+        .collect())
+}
+
+/// The syntax tree of a regex.
+enum Node {
+    /// A bracketed class, or a single character.
+    Chars(Vec<char>),
+    Concat(Vec<Node>),
+    Alternation(Vec<Node>),
+    /// A group, with its byte range in the regex.
+    Group(Box<Node>, Range<usize>),
+    /// A repetition, with its lowest and highest counts.
+    Repetition(Box<Node>, u32, u32),
+}
+
+struct Parser<'r> {
+    regex: &'r str,
+    chars: Peekable<CharIndices<'r>>,
+}
+
+impl<'r> Parser<'r> {
+    fn new(regex: &'r str) -> Self {
+        Parser {
+            regex,
+            chars: regex.char_indices().peekable(),
+        }
+    }
+
+    fn parse(mut self) -> Result<Node, String> {
+        let node = self.alternation()?;
+
+        match self.chars.next() {
+            Some((_, c)) => Err(format!("unmatched `{c}`")),
+            None => Ok(node),
+        }
+    }
+
+    /// The byte offset of the next character.
+    fn position(&mut self) -> usize {
+        self.chars
+            .peek()
+            .map_or(self.regex.len(), |&(index, _)| index)
+    }
+
+    /// Skips the next character if it is `c`.
+    fn eat(&mut self, c: char) -> bool {
+        self.chars.next_if(|&(_, next)| next == c).is_some()
+    }
+
+    fn alternation(&mut self) -> Result<Node, String> {
+        let mut nodes = vec![self.concat()?];
+        while self.eat('|') {
+            nodes.push(self.concat()?);
+        }
+
+        Ok(if nodes.len() == 1 {
+            nodes.remove(0)
+        } else {
+            Node::Alternation(nodes)
+        })
+    }
+
+    fn concat(&mut self) -> Result<Node, String> {
+        let mut nodes = Vec::new();
+
+        while let Some(&(start, c)) = self.chars.peek() {
+            if c == '|' || c == ')' {
+                break;
+            }
+            self.chars.next();
+
+            let node = match c {
+                '(' => {
+                    // Only the non-capturing groups have a syntax of their own
+                    if self.eat('?') && !self.eat(':') {
+                        return Err("group flags and lookarounds aren't supported".to_string());
+                    }
+                    let node = self.alternation()?;
+                    if !self.eat(')') {
+                        return Err("unclosed group".to_string());
+                    }
+                    Node::Group(Box::new(node), start..self.position())
+                }
+                '[' => self.class()?,
+                '\\' => Node::Chars(vec![self.escaped()?]),
+                '.' => return Err("the wildcard `.` isn't supported".to_string()),
+                '^' | '$' => return Err(format!("the anchor `{c}` isn't supported")),
+                '?' | '*' | '+' | '{' => return Err(format!("nothing to repeat with `{c}`")),
+                c => Node::Chars(vec![c]),
+            };
+
+            nodes.push(self.repetition(node)?);
+        }
+
+        Ok(Node::Concat(nodes))
+    }
+
+    fn repetition(&mut self, node: Node) -> Result<Node, String> {
+        let (min, max) = match self.chars.peek() {
+            Some((_, '?')) => (0, 1),
+            Some((_, c @ ('*' | '+'))) => {
+                return Err(format!("the unbounded repetition `{c}` isn't supported"));
+            }
+            Some((_, '{')) => {
+                self.chars.next();
+                let start = self.position();
+                while !self.eat('}') {
+                    if self.chars.next().is_none() {
+                        return Err("unclosed repetition".to_string());
+                    }
+                }
+
+                let range = &self.regex[start..self.position() - 1];
+                let bound = |count: &str| {
+                    count
+                        .trim()
+                        .parse::<u32>()
+                        .map_err(|_| format!("invalid repetition `{{{range}}}`"))
+                };
+                let (min, max) = match range.split_once(',') {
+                    None => (bound(range)?, bound(range)?),
+                    Some((_, max)) if max.trim().is_empty() => {
+                        return Err(format!(
+                            "the unbounded repetition `{{{range}}}` isn't supported"
+                        ));
+                    }
+                    Some((min, max)) => (bound(min)?, bound(max)?),
+                };
+
+                if min > max || max > MAX_REPETITION {
+                    return Err(format!("invalid repetition `{{{range}}}`"));
+                }
+                // The lazy repetitions match the same texts
+                self.eat('?');
+                return Ok(Node::Repetition(Box::new(node), min, max));
+            }
+            _ => return Ok(node),
+        };
+
+        self.chars.next();
+        self.eat('?');
+        Ok(Node::Repetition(Box::new(node), min, max))
+    }
+
+    /// Parses a bracketed class after its `[`.
+    fn class(&mut self) -> Result<Node, String> {
+        if self.eat('^') {
+            return Err("negated classes aren't supported".to_string());
+        }
+
+        let mut chars = Vec::new();
+        loop {
+            let c = match self.chars.next() {
+                // A `]` is literal at the start of the class
+                Some((_, ']')) if !chars.is_empty() => break,
+                Some((_, '[')) => return Err("nested classes aren't supported".to_string()),
+                Some((_, '\\')) => self.escaped()?,
+                Some((_, c)) => c,
+                None => return Err("unclosed class".to_string()),
+            };
+
+            if matches!(c, '&' | '-' | '~') && self.eat(c) {
+                return Err(format!("the class operation `{c}{c}` isn't supported"));
+            }
+
+            // A `-` is literal at the end of the class
+            let mut ahead = self.chars.clone();
+            if ahead.next_if(|&(_, next)| next == '-').is_some()
+                && !matches!(ahead.peek(), Some((_, ']')) | None)
+            {
+                self.chars.next();
+                let end = match self.chars.next() {
+                    Some((_, '\\')) => self.escaped()?,
+                    Some((_, end)) => end,
+                    None => return Err("unclosed class".to_string()),
+                };
+                if end < c {
+                    return Err(format!("invalid class range `{c}-{end}`"));
+                }
+                chars.extend(c..=end);
+            } else {
+                chars.push(c);
+            }
+        }
+
+        Ok(Node::Chars(chars))
+    }
+
+    /// Parses an escaped character after its `\`.
+    fn escaped(&mut self) -> Result<char, String> {
+        match self.chars.next() {
+            Some((_, c)) if c.is_ascii_punctuation() => Ok(c),
+            Some((_, c)) => Err(format!("the escape `\\{c}` isn't supported")),
+            None => Err("trailing `\\`".to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::normalization::normalize;
+
+    #[test]
+    fn test_expand() {
+        let mut expander = Expander::default();
+        let mut expand = |regex: &str| expander.expand(regex);
+
+        assert_eq!(expand("ক্স").unwrap(), ["ক্স"]);
+        assert_eq!(expand("(য|জ)").unwrap(), ["জ", "য"]);
+        assert_eq!(expand("((য)|(জ)|)").unwrap(), ["", "জ", "য"]);
+        assert_eq!(expand("(ক|খ)(গ)?").unwrap(), ["ক", "কগ", "খ", "খগ"]);
+        assert_eq!(expand("[ওোঅ]|(অ্য)").unwrap(), ["অ", "অ্য", "ও", "ো"]);
+        assert_eq!(expand("(?:a[x-z-]){1,2}").unwrap().len(), 20);
+        assert_eq!(expand(r"\(ক\)").unwrap(), ["(ক)"]);
+        assert_eq!(expand("").unwrap(), [""]);
+
+        // The errors tell which construct isn't supported
+        assert_eq!(
+            expand("জ+").unwrap_err(),
+            "the unbounded repetition `+` isn't supported"
+        );
+        assert_eq!(
+            expand("জ{2,}").unwrap_err(),
+            "the unbounded repetition `{2,}` isn't supported"
+        );
+        assert_eq!(
+            expand("[^জ]").unwrap_err(),
+            "negated classes aren't supported"
+        );
+        assert_eq!(expand("(জ").unwrap_err(), "unclosed group");
+        assert_eq!(expand("জ)").unwrap_err(), "unmatched `)`");
+        assert_eq!(
+            expand(r"\d").unwrap_err(),
+            "the escape `\\d` isn't supported"
+        );
+        assert_eq!(
+            expand("[a-z]{4}").unwrap_err(),
+            "it matches more than 65536 texts"
+        );
+    }
+
+    #[test]
+    fn test_embedded_patterns() {
+        // The same as the patterns expanded by the `generate` tool
+        let patterns: RegexPatterns =
+            serde_json::from_slice(include_bytes!("../../data/source-regex-patterns.json"))
+                .unwrap();
+        let preprocessed: HashMap<String, Block> =
+            serde_json::from_slice(include_bytes!("../../data/preprocessed-patterns.json"))
+                .unwrap();
+
+        // The texts are sorted in the form of the source file
+        let normalized = |block: &Block| {
+            let mut texts: Vec<_> = block
+                .transliterate
+                .iter()
+                .filter(|text| !text.is_empty())
+                .map(|text| normalize(text))
+                .collect();
+            texts.sort();
+            (texts, block.entire_block_optional)
+        };
+
+        let mut expander = Expander::default();
+        assert_eq!(patterns.len(), preprocessed.len());
+        for (key, block) in patterns {
+            let block = block.into_block(&key, &mut expander).unwrap();
+            assert_eq!(normalized(&block), normalized(&preprocessed[&key]));
+        }
+    }
+
+    #[test]
+    fn test_regex_block() {
+        let overrides: Overrides = serde_json::from_str(
+            r#"{ "z": { "transliterate": "(য|জ)", "_comment": "ignored" },
+                "o": { "transliterate": "(ও|অ)", "entireBlockOptional": true },
+                "q": null }"#,
+        )
+        .unwrap();
+
+        let mut expander = Expander::default();
+        let mut overrides: Vec<_> = overrides.into_iter().collect();
+        overrides.sort_by(|(a, _), (b, _)| a.cmp(b));
+        let mut blocks = overrides
+            .into_iter()
+            .map(|(key, block)| block.map(|block| block.into_block(&key, &mut expander).unwrap()));
+
+        let o = blocks.next().unwrap().unwrap();
+        assert_eq!(o.transliterate, ["অ", "ও"]);
+        assert_eq!(o.entire_block_optional, Some(true));
+        assert!(blocks.next().unwrap().is_none());
+        assert_eq!(blocks.next().unwrap().unwrap().transliterate, ["জ", "য"]);
+
+        let block = RegexBlock {
+            transliterate: "জ+".to_string(),
+            entire_block_optional: None,
+        };
+        assert!(matches!(
+            block.into_block("j", &mut expander),
+            Err(Error::UnsupportedRegex { key, regex, .. }) if key == "j" && regex == "জ+"
+        ));
+    }
+}
@@ -0,0 +1,289 @@
+use std::{collections::HashMap, ops::Range};
+
+use serde::Deserialize;
+
+use crate::normalization::normalize;
+
+/// The rule-based Avro Phonetic conversion, in the JSON format of
+/// `phonetic-rules.json`.
+#[derive(Deserialize)]
+#[serde(try_from = "RawPhonetic")]
+pub(crate) struct Phonetic {
+    vowels: String,
+    consonants: String,
+    /// The letters whose uppercase form has a different meaning.
+    case_sensitive: String,
+    patterns: HashMap<String, Pattern>,
+    /// The length of the longest pattern.
+    longest: usize,
+}
+
+#[derive(Deserialize)]
+struct RawPhonetic {
+    vowel: String,
+    consonant: String,
+    casesensitive: String,
+    patterns: Vec<Pattern>,
+}
+
+#[derive(Deserialize)]
+struct Pattern {
+    find: String,
+    replace: String,
+    /// The replacements used instead of `replace` in some contexts. The first
+    /// rule whose conditions all match is used.
+    #[serde(default)]
+    rules: Vec<Rule>,
+}
+
+#[derive(Deserialize)]
+struct Rule {
+    matches: Vec<Match>,
+    replace: String,
+}
+
+#[derive(Deserialize)]
+#[serde(try_from = "RawMatch")]
+struct Match {
+    /// Whether the text after the pattern is checked instead of before.
+    suffix: bool,
+    negated: bool,
+    scope: Scope,
+}
+
+#[derive(Deserialize)]
+struct RawMatch {
+    #[serde(rename = "type")]
+    kind: String,
+    scope: String,
+    value: Option<String>,
+}
+
+enum Scope {
+    Vowel,
+    Consonant,
+    Punctuation,
+    Number,
+    Exact(String),
+}
+
+impl TryFrom<RawPhonetic> for Phonetic {
+    type Error = String;
+
+    fn try_from(raw: RawPhonetic) -> Result<Self, Self::Error> {
+        let longest = raw.patterns.iter().map(|p| p.find.len()).max().unwrap_or(0);
+        let mut patterns = HashMap::new();
+
+        for pattern in raw.patterns {
+            if pattern.find.is_empty() {
+                return Err("Empty phonetic pattern".to_string());
+            }
+
+            patterns.insert(pattern.find.clone(), pattern);
+        }
+
+        Ok(Phonetic {
+            vowels: raw.vowel,
+            consonants: raw.consonant,
+            case_sensitive: raw.casesensitive,
+            patterns,
+            longest,
+        })
+    }
+}
+
+impl TryFrom<RawMatch> for Match {
+    type Error = String;
+
+    fn try_from(raw: RawMatch) -> Result<Self, Self::Error> {
+        let suffix = match raw.kind.as_str() {
+            "prefix" => false,
+            "suffix" => true,
+            kind => return Err(format!("Unknown match type {kind:?}")),
+        };
+
+        let (negated, scope) = match raw.scope.strip_prefix('!') {
+            Some(scope) => (true, scope),
+            None => (false, raw.scope.as_str()),
+        };
+
+        let scope = match (scope, raw.value) {
+            ("vowel", _) => Scope::Vowel,
+            ("consonant", _) => Scope::Consonant,
+            ("punctuation", _) => Scope::Punctuation,
+            ("number", _) => Scope::Number,
+            ("exact", Some(value)) => Scope::Exact(value),
+            ("exact", None) => return Err("Missing the value of an exact match".to_string()),
+            (scope, _) => return Err(format!("Unknown match scope {scope:?}")),
+        };
+
+        Ok(Match {
+            suffix,
+            negated,
+            scope,
+        })
+    }
+}
+
+impl Phonetic {
+    /// Converts the Avro `input` into Bangla, whether or not it is a
+    /// dictionary word.
+    ///
+    /// The longest pattern is replaced at each position, and the characters
+    /// which don't match any pattern are kept as they are.
+    pub fn convert(&self, input: &str) -> String {
+        self.convert_range(input, 0..input.len())
+    }
+
+    /// Converts the part of the Avro `input` in the byte `range`, with the
+    /// rest of the input as the context of the rules.
+    pub fn convert_range(&self, input: &str, range: Range<usize>) -> String {
+        if range.is_empty() {
+            return String::new();
+        }
+
+        let input: String = input
+            .chars()
+            .map(|c| {
+                if self.case_sensitive.contains(c.to_ascii_lowercase()) {
+                    c
+                } else {
+                    c.to_ascii_lowercase()
+                }
+            })
+            .collect();
+
+        let mut output = String::with_capacity(range.len() * 3);
+        let mut position = range.start;
+
+        while position < range.end {
+            let pattern = (1..=self.longest).rev().find_map(|len| {
+                let find = input.get(position..range.end.min(position + len))?;
+                self.patterns.get(find)
+            });
+
+            match pattern {
+                Some(pattern) => {
+                    let (before, after) =
+                        (&input[..position], &input[position + pattern.find.len()..]);
+                    let replace = pattern
+                        .rules
+                        .iter()
+                        .find(|rule| rule.matches.iter().all(|m| self.matches(m, before, after)))
+                        .map_or(&pattern.replace, |rule| &rule.replace);
+
+                    output.push_str(replace);
+                    position += pattern.find.len();
+                }
+                None => {
+                    let c = input[position..].chars().next().unwrap();
+                    output.push(c);
+                    position += c.len_utf8();
+                }
+            }
+        }
+
+        normalize(&output)
+    }
+
+    /// Returns the forms of the lowercase `input` with any of its case
+    /// sensitive letters in uppercase, the `input` itself first.
+    pub fn case_variants(&self, input: &str) -> Vec<String> {
+        let mut variants = vec![String::new()];
+
+        for c in input.chars() {
+            let upper = c.to_ascii_uppercase();
+            let cased = upper != c && self.case_sensitive.contains(c);
+
+            variants = variants
+                .into_iter()
+                .flat_map(|variant| {
+                    let lower = format!("{variant}{c}");
+                    let upper = cased.then(|| format!("{variant}{upper}"));
+                    [Some(lower), upper].into_iter().flatten()
+                })
+                .collect();
+        }
+
+        variants
+    }
+
+    /// Whether the text `before` and `after` a pattern satisfies the `condition`.
+    fn matches(&self, condition: &Match, before: &str, after: &str) -> bool {
+        // The start and the end of the input count as spaces
+        let c = if condition.suffix {
+            after.chars().next()
+        } else {
+            before.chars().next_back()
+        }
+        .unwrap_or(' ');
+
+        let matched = match &condition.scope {
+            Scope::Vowel => self.is_vowel(c),
+            Scope::Consonant => self.is_consonant(c),
+            Scope::Punctuation => !self.is_vowel(c) && !self.is_consonant(c),
+            Scope::Number => c.is_ascii_digit(),
+            Scope::Exact(value) if condition.suffix => after.starts_with(value.as_str()),
+            Scope::Exact(value) => before.ends_with(value.as_str()),
+        };
+
+        matched != condition.negated
+    }
+
+    fn is_vowel(&self, c: char) -> bool {
+        c.is_ascii_alphabetic() && self.vowels.contains(c.to_ascii_lowercase())
+    }
+
+    fn is_consonant(&self, c: char) -> bool {
+        c.is_ascii_alphabetic() && self.consonants.contains(c.to_ascii_lowercase())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conversion() {
+        let phonetic: Phonetic =
+            serde_json::from_slice(include_bytes!("../../data/phonetic-rules.json")).unwrap();
+
+        assert_eq!(phonetic.convert(""), "");
+        assert_eq!(phonetic.convert("ami"), "আমি");
+        assert_eq!(phonetic.convert("amar sOnar bangla"), "আমার সোনার বাংলা");
+        assert_eq!(phonetic.convert("t6th"), "ত৬থ");
+        assert_eq!(phonetic.convert("kOtha"), "কোথা");
+        assert_eq!(phonetic.convert("Ami"), "আমি");
+        assert_eq!(phonetic.convert("ami."), "আমি।");
+        assert_eq!(phonetic.convert("1.5"), "১.৫");
+        assert_eq!(phonetic.convert("rZab"), "র\u{200D}্যাব");
+        assert_eq!(phonetic.convert("k`h"), "কহ");
+        assert_eq!(phonetic.convert("ঢাকা"), "ঢাকা");
+
+        assert_eq!(phonetic.case_variants("sh"), ["sh", "Sh"]);
+        assert_eq!(phonetic.case_variants("ng"), ["ng", "nG", "Ng", "NG"]);
+        assert_eq!(phonetic.case_variants("ka"), ["ka"]);
+
+        // The rest of the input is the context
+        assert_eq!(phonetic.convert_range("ami.", 3..4), "।");
+        assert_eq!(phonetic.convert_range(".5", 0..1), ".");
+        assert_eq!(phonetic.convert_range("ami.", 0..3), "আমি");
+    }
+
+    #[test]
+    fn test_invalid_rules() {
+        let parse = |json: &str| serde_json::from_str::<Phonetic>(json);
+        let rules = |scope: &str| {
+            format!(
+                r#"{{ "vowel": "a", "consonant": "k", "casesensitive": "",
+                    "patterns": [{{ "find": "k", "replace": "ক",
+                    "rules": [{{ "matches": [{{ "type": "prefix", "scope": "{scope}" }}], "replace": "ক্ক" }}] }}] }}"#
+            )
+        };
+
+        assert!(parse(&rules("!vowel")).is_ok());
+        assert!(parse(&rules("letter")).is_err());
+        assert!(parse(&rules("exact")).is_err());
+        assert!(parse(r#"{ "vowel": "a" }"#).is_err());
+    }
+}
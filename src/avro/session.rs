@@ -0,0 +1,166 @@
+use crate::{
+    avro::{
+        suggest::{start_candidates, Candidate, Segments, Suggest},
+        utils::fix_string,
+    },
+    fst::FstData,
+};
+
+/// The Avro input being typed, for suggesting words after every keystroke.
+///
+/// The words FST nodes reached at each position of the input are kept
+/// between the keystrokes, so that only the positions after the first one
+/// changed by a keystroke are followed again in the FST. Usually, that is
+/// just the last one. The whole input is still split into the pattern
+/// segments again on every keystroke, as a new character may extend a
+/// pattern starting before it, so that part grows with the input.
+///
+/// The suggestions are the same as those of [`Suggest::suggest`] for the
+/// whole input. As the session borrows the suggester, it has to be dropped
+/// before recording a selection or changing the user dictionary.
+pub struct Session<'s> {
+    suggest: &'s Suggest,
+    input: String,
+    segments: Segments<'s>,
+    /// The candidates at each position of the input, in the built-in words
+    /// and in the user dictionary.
+    builtin: Vec<Vec<Candidate<'s, 's, FstData>>>,
+    user: Vec<Vec<Candidate<'s, 's, Vec<u8>>>>,
+}
+
+impl<'s> Session<'s> {
+    /// Starts a session with an empty input.
+    pub fn new(suggest: &'s Suggest) -> Self {
+        Session {
+            suggest,
+            input: String::new(),
+            segments: suggest.segments(""),
+            builtin: vec![start_candidates(suggest.words(), false)],
+            user: vec![start_candidates(suggest.user_dictionary().tree(), false)],
+        }
+    }
+
+    /// Returns the input typed so far.
+    pub fn input(&self) -> &str {
+        &self.input
+    }
+
+    /// Types the character `c` at the end of the input.
+    pub fn push_char(&mut self, c: char) {
+        self.input.push(c);
+        self.update();
+    }
+
+    /// Removes the last character of the input, like with a backspace, and
+    /// returns it.
+    pub fn pop_char(&mut self) -> Option<char> {
+        let c = self.input.pop()?;
+        self.update();
+        Some(c)
+    }
+
+    /// Clears the input, like after committing a word.
+    pub fn reset(&mut self) {
+        self.input.clear();
+        self.segments = self.suggest.segments("");
+        self.builtin.truncate(1);
+        self.user.truncate(1);
+    }
+
+    /// Returns the suggestions for the input typed so far.
+    pub fn suggestions(&self) -> Vec<String> {
+        let suggest = self.suggest;
+        let input = suggest.split_input(&self.input);
+
+        // Nothing is matched by an empty input
+        let end = self.segments.end;
+        let (builtin, user) = if end == 0 {
+            (&[][..], &[][..])
+        } else {
+            (&self.builtin[end][..], &self.user[end][..])
+        };
+
+        let words = suggest.candidate_words(&fix_string(input.word()), builtin, user);
+        suggest.arrange(&input, words)
+    }
+
+    /// Splits the whole input into segments again, and follows the positions
+    /// after the first one changed since the last update.
+    fn update(&mut self) {
+        let suggest = self.suggest;
+        let input = suggest.split_input(&self.input);
+        let segments = suggest.segments(input.word());
+
+        // The candidates up to the first changed position are still valid
+        let unchanged = self.segments.unchanged_len(&segments);
+
+        self.builtin.truncate(unchanged + 1);
+        self.user.truncate(unchanged + 1);
+
+        for end in unchanged + 1..=segments.end {
+            let builtin = suggest.candidates_at(&self.builtin, &segments, end);
+            let user = suggest.candidates_at(&self.user, &segments, end);
+
+            self.builtin.push(builtin);
+            self.user.push(user);
+        }
+
+        self.segments = segments;
+    }
+}
+
+#[cfg(all(test, feature = "embedded"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session() {
+        let mut suggest = Suggest::new();
+        suggest.user_dictionary_mut().add("অংশোচ্ছেদ");
+        let mut session = Session::new(&suggest);
+        assert!(session.suggestions().is_empty());
+
+        // The same as the suggestions for the whole input after each keystroke
+        for c in "ongshochched".chars() {
+            session.push_char(c);
+            assert_eq!(session.suggestions(), suggest.suggest(session.input()));
+        }
+        assert!(session.suggestions().contains(&"অংশোচ্ছেদ".to_string()));
+
+        while session.pop_char().is_some() {
+            assert_eq!(session.suggestions(), suggest.suggest(session.input()));
+        }
+        assert_eq!(session.pop_char(), None);
+
+        // With punctuation and segments changed by the next characters
+        for c in "(k`hobor".chars() {
+            session.push_char(c);
+            assert_eq!(session.suggestions(), suggest.suggest(session.input()));
+        }
+        session.pop_char();
+        session.pop_char();
+        session.push_char(',');
+        assert_eq!(session.input(), "(k`hob,");
+        assert_eq!(session.suggestions(), suggest.suggest("(k`hob,"));
+
+        // The case of the same segments matters in the case-sensitive mode
+        let mut suggest = Suggest::new();
+        suggest.set_case_sensitive(true);
+        let mut session = suggest.session();
+        for c in "kaTa".chars() {
+            session.push_char(c);
+        }
+        session.pop_char();
+        session.pop_char();
+        session.push_char('t');
+        session.push_char('a');
+        assert_eq!(session.suggestions(), suggest.suggest("kata"));
+        assert_ne!(session.suggestions(), suggest.suggest("kaTa"));
+
+        session.reset();
+        assert_eq!(session.input(), "");
+        assert!(session.suggestions().is_empty());
+        session.push_char('a');
+        assert_eq!(session.suggestions(), suggest.suggest("a"));
+    }
+}
@@ -1,133 +1,1953 @@
-use std::collections::{HashMap, HashSet};
+use std::{
+    borrow::Cow,
+    cmp::Reverse,
+    collections::{HashMap, HashSet},
+    fs, io,
+    ops::Range,
+    path::{Path, PathBuf},
+};
 
-use once_cell::sync::Lazy;
+use once_cell::sync::OnceCell;
 use serde::Deserialize;
 
-use crate::{avro::utils::fix_string, fst::FstTree, WORDS};
+use crate::{
+    avro::{
+        autocorrect::Autocorrect,
+        history::SelectionHistory,
+        patterns::{Expander, Overrides, RegexPatterns},
+        phonetic::Phonetic,
+        session::Session,
+        utils::{distance, fix_string, word_range},
+    },
+    fst::{
+        best_words, best_words_shortest_first, ranked_words, FstData, FstNode, FstTree, TextSet,
+        MAX_WEIGHT,
+    },
+    normalization::normalize,
+    suggestion::{rank, Source, Suggestion},
+    user_dictionary::UserDictionary,
+    Error,
+};
 
-static PATTERNS: Lazy<FstTree<&[u8]>> =
-    Lazy::new(|| FstTree::from_fst(include_bytes!("patterns.fst")));
+/// The transliterations of an Avro pattern.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Block {
+    /// The Bangla texts the pattern may stand for.
+    pub transliterate: Vec<String>,
+    /// Whether the pattern may also stand for nothing at all, like `o` in
+    /// the middle of a word.
+    pub entire_block_optional: Option<bool>,
+}
+
+pub struct Suggest {
+    words: FstTree<FstData>,
+    /// The FST of the pattern keys, for finding the patterns at each position
+    /// of the input.
+    keys: FstTree<Vec<u8>>,
+    patterns: HashMap<String, Block>,
+    /// The transliterations of each pattern, for following them all at once.
+    texts: HashMap<String, TextSet>,
+    common_suffixes: Vec<String>,
+    /// The common suffixes, for following them all at once.
+    suffixes: TextSet,
+    phonetic: Phonetic,
+    autocorrect: Autocorrect,
+    /// The English words, if any.
+    english: Option<FstTree<FstData>>,
+    /// Whether the phonetic conversion is suggested first.
+    include_conversion: bool,
+    /// Whether the input is suggested if it is an English word.
+    include_english: bool,
+    /// Whether the uppercase letters of the input narrow down the patterns.
+    case_sensitive: bool,
+    /// The number of the words extending the input which are suggested.
+    completion_limit: usize,
+    /// The number of the words FST nodes kept for each position of the input.
+    beam_width: usize,
+    unmatched: Unmatched,
+    user_dictionary: UserDictionary,
+    history: SelectionHistory,
+    /// The expansions of the regex groups, kept for the later overrides of the
+    /// patterns.
+    expander: Expander,
+    /// The pattern keys of each transliteration, built on the first reverse
+    /// transliteration.
+    reverse_patterns: OnceCell<HashMap<String, Vec<ReverseKey>>>,
+}
+
+/// The Avro input split into the word and the punctuation around it.
+pub(super) struct Input<'i> {
+    /// The trimmed input.
+    text: &'i str,
+    /// The byte offset of the text in the whole input.
+    offset: usize,
+    /// The byte range of the word in the text.
+    word: Range<usize>,
+    /// The punctuation before the word, converted into Bangla.
+    prefix: String,
+    /// The punctuation after the word, converted into Bangla.
+    suffix: String,
+}
+
+impl Input<'_> {
+    pub fn word(&self) -> &str {
+        &self.text[self.word.clone()]
+    }
+
+    /// The byte offset of the word in the whole input.
+    fn word_offset(&self) -> usize {
+        self.offset + self.word.start
+    }
+
+    /// Surrounds the suggested `word` with the punctuation of the input.
+    fn punctuate(&self, word: &str) -> String {
+        format!("{}{word}{}", self.prefix, self.suffix)
+    }
+
+    /// Surrounds the suggested `words` with the punctuation of the input. If
+    /// there is no word in the input, the punctuation alone is suggested.
+    fn punctuate_all(&self, words: Vec<String>) -> Vec<String> {
+        if self.word.is_empty() && !self.prefix.is_empty() {
+            return vec![self.prefix.clone()];
+        }
+        if self.prefix.is_empty() && self.suffix.is_empty() {
+            return words;
+        }
+
+        words.iter().map(|word| self.punctuate(word)).collect()
+    }
+}
+
+/// The number of the most natural inputs kept for each position of the word
+/// in a reverse transliteration.
+const REVERSE_BEAM: usize = 32;
+
+/// The highest number of inputs returned by a reverse transliteration.
+const REVERSE_LIMIT: usize = 8;
+
+/// The number of the words FST nodes kept for each position of the input by
+/// default.
+const DEFAULT_BEAM_WIDTH: usize = 512;
+
+/// The length of the shortest Avro word for which searching the best words is
+/// faster than collecting all of them, as the shorter ones match few words.
+const MIN_SEARCH_LEN: usize = 6;
+
+/// The characters of the input normalized by `fix_string()`, for the keys of
+/// the segments which aren't patterns.
+const INPUT_CHARS: &str = "`0123456789abcdefghijklmnopqrstuvwxyzO";
+
+/// How the characters of the Avro input which don't match any pattern are
+/// handled, when there is no other way to split the input.
+///
+/// The embedded patterns match all the letters and digits, but a custom
+/// pattern table may not.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Unmatched {
+    /// The words match the input up to the first unmatched character, and
+    /// the rest is left unconverted.
+    #[default]
+    LongestPrefix,
+    /// The unmatched characters match themselves in the words.
+    ///
+    /// The built-in words are all Bangla, so this only helps with the words
+    /// of the user dictionary, or of a custom words FST, which have these
+    /// characters, like `কx`. Otherwise, no word matches the input.
+    Literal,
+    /// The unmatched characters are skipped, as if they weren't typed.
+    Skip,
+}
+
+impl Suggest {
+    /// Creates a suggester with the embedded word list and pattern tables.
+    #[cfg(feature = "embedded")]
+    pub fn new() -> Self {
+        Self::try_new().expect("The embedded data is valid")
+    }
+
+    /// Creates a suggester with the embedded word list and pattern tables,
+    /// returning an error instead of panicking if they are invalid.
+    #[cfg(feature = "embedded")]
+    pub fn try_new() -> Result<Self, Error> {
+        Self::builder().build()
+    }
+
+    /// Returns a builder for loading the word list and pattern tables at runtime.
+    pub fn builder() -> SuggestBuilder {
+        SuggestBuilder::default()
+    }
+
+    /// Starts a [`Session`] for suggesting words while the input is typed.
+    pub fn session(&self) -> Session<'_> {
+        Session::new(self)
+    }
+
+    /// Returns the FST of the built-in words.
+    pub(super) fn words(&self) -> &FstTree<FstData> {
+        &self.words
+    }
+
+    /// Returns the user dictionary whose words are suggested along with the
+    /// built-in words.
+    pub fn user_dictionary(&self) -> &UserDictionary {
+        &self.user_dictionary
+    }
+
+    /// Returns the user dictionary for adding or removing words.
+    pub fn user_dictionary_mut(&mut self) -> &mut UserDictionary {
+        &mut self.user_dictionary
+    }
+
+    /// Replaces the user dictionary.
+    pub fn set_user_dictionary(&mut self, dictionary: UserDictionary) {
+        self.user_dictionary = dictionary;
+    }
+
+    /// Records that the user selected the `word` for the Avro `input`, so that
+    /// it is ranked higher in the future suggestions for the input.
+    ///
+    /// The punctuation of the input is removed from the word, so that the
+    /// selection counts for the input with any punctuation.
+    pub fn record_selection(&mut self, input: &str, word: &str) {
+        let input = self.split_input(input);
+        let word = word
+            .strip_prefix(input.prefix.as_str())
+            .and_then(|word| word.strip_suffix(input.suffix.as_str()))
+            .filter(|word| !word.is_empty())
+            .unwrap_or(word);
+
+        self.history.record(input.word(), word);
+    }
+
+    /// Returns the history of the user's selections.
+    pub fn selection_history(&self) -> &SelectionHistory {
+        &self.history
+    }
+
+    /// Replaces the history of the user's selections, like with a previously
+    /// saved one.
+    pub fn set_selection_history(&mut self, history: SelectionHistory) {
+        self.history = history;
+    }
+
+    /// Returns the block of the pattern `key`.
+    pub fn pattern(&self, key: &str) -> Option<&Block> {
+        self.patterns.get(key)
+    }
+
+    /// Adds the pattern `key` with the transliterations of the `block`, or
+    /// replaces its block, and returns the replaced one.
+    ///
+    /// Like the input it matches, the key is made of lowercase letters,
+    /// digits and `` ` ``, with `O` for an `o` starting a word. The block must
+    /// have at least one transliteration.
+    pub fn set_pattern(&mut self, key: &str, block: Block) -> Result<Option<Block>, Error> {
+        let block = checked_block(key, block)?;
+        self.texts.insert(key.to_string(), texts_of(&block));
+        let replaced = self.patterns.insert(key.to_string(), block);
+        self.rebuild_patterns();
+        Ok(replaced)
+    }
+
+    /// Removes the pattern `key`, and returns its block.
+    pub fn remove_pattern(&mut self, key: &str) -> Option<Block> {
+        let removed = self.patterns.remove(key)?;
+        self.texts.remove(key);
+        self.rebuild_patterns();
+        Some(removed)
+    }
+
+    /// Adds, replaces or removes the patterns given in the JSON format of
+    /// `source-regex-patterns.json`, like regional spellings over the
+    /// embedded patterns:
+    ///
+    /// ```json
+    /// {
+    ///   "z": { "transliterate": "(য|জ)" },
+    ///   "x": { "transliterate": "ক্স" },
+    ///   "q": null
+    /// }
+    /// ```
+    ///
+    /// The transliterations are expanded from the regexes like with
+    /// [`SuggestBuilder::regex_patterns`], and a `null` block removes the
+    /// pattern. If any of the patterns is invalid, none of them are changed.
+    pub fn override_patterns(&mut self, json: impl AsRef<[u8]>) -> Result<(), Error> {
+        let overrides: Overrides =
+            serde_json::from_slice(json.as_ref()).map_err(Error::InvalidPatterns)?;
+        let mut blocks = Vec::with_capacity(overrides.len());
+
+        for (key, block) in overrides {
+            let block = match block {
+                Some(block) => Some(checked_block(
+                    &key,
+                    block.into_block(&key, &mut self.expander)?,
+                )?),
+                None => None,
+            };
+            blocks.push((key, block));
+        }
+
+        for (key, block) in blocks {
+            match block {
+                Some(block) => {
+                    self.texts.insert(key.clone(), texts_of(&block));
+                    self.patterns.insert(key, block);
+                }
+                None => {
+                    self.texts.remove(&key);
+                    self.patterns.remove(&key);
+                }
+            }
+        }
+
+        self.rebuild_patterns();
+        Ok(())
+    }
+
+    /// Reads the pattern overrides of [`Suggest::override_patterns`] from
+    /// the file at `path`.
+    pub fn override_patterns_file(&mut self, path: impl AsRef<Path>) -> Result<(), Error> {
+        self.override_patterns(fs::read(path)?)
+    }
+
+    /// Rebuilds the lookup structures of the patterns after they changed.
+    fn rebuild_patterns(&mut self) {
+        self.keys = pattern_keys(&self.patterns);
+        self.reverse_patterns = OnceCell::new();
+    }
+
+    /// Converts the Avro `input` into Bangla with the Avro Phonetic rules,
+    /// whether or not it is a dictionary word.
+    pub fn convert(&self, input: &str) -> String {
+        self.phonetic.convert(input.trim())
+    }
+
+    /// Sets whether the phonetic conversion of the input is suggested first,
+    /// before the dictionary words. It is off by default.
+    ///
+    /// This way, the input can be typed even if it isn't in the dictionary,
+    /// like names and new words.
+    pub fn set_include_conversion(&mut self, include: bool) {
+        self.include_conversion = include;
+    }
+
+    /// Returns whether the phonetic conversion of the input is suggested first.
+    pub fn includes_conversion(&self) -> bool {
+        self.include_conversion
+    }
+
+    /// Sets whether the input itself is suggested if it is a known English
+    /// word, right after the best Bangla word. It is off by default.
+    ///
+    /// This way, English words can be typed without leaving the Avro mode,
+    /// like `computer` along with `কম্পিউটার`. The embedded English words are
+    /// only a small sample of common words, to be replaced with a full list
+    /// with [`SuggestBuilder::english_words_fst_file`].
+    pub fn set_include_english(&mut self, include: bool) {
+        self.include_english = include;
+    }
+
+    /// Returns whether the input itself is suggested if it is an English word.
+    pub fn includes_english(&self) -> bool {
+        self.include_english
+    }
+
+    /// Sets whether the uppercase letters of the input must be transliterated
+    /// as in the classic Avro Phonetic. It is off by default, when the case
+    /// doesn't matter.
+    ///
+    /// In the case-sensitive mode, `T`, `Th`, `D`, `Dh`, `N`, `R`, `Rh`, `S`,
+    /// `Sh`, `J`, `Ng` and `NG` only match the patterns with `ট`, `ঠ`, `ড`,
+    /// `ঢ`, `ণ`, `ড়`, `ঢ়`, `শ`, `ষ`, `জ`, `ঙ` and `ঞ`, so `Tk` only suggests
+    /// the words with `ট`. The other letters match as in the lowercase.
+    pub fn set_case_sensitive(&mut self, case_sensitive: bool) {
+        self.case_sensitive = case_sensitive;
+    }
+
+    /// Returns whether the uppercase letters of the input narrow down the
+    /// patterns.
+    pub fn is_case_sensitive(&self) -> bool {
+        self.case_sensitive
+    }
+
+    /// Sets the number of the words suggested which extend the words matching
+    /// the input, like `বিস্তারিত` for `bist`. They come after the words
+    /// matching the input, ordered by descending word weight and then by
+    /// length, the shortest first.
+    ///
+    /// It is `0` by default, when only the words matching the entire input
+    /// are suggested.
+    pub fn set_completion_limit(&mut self, limit: usize) {
+        self.completion_limit = limit;
+    }
+
+    /// Returns the number of the words suggested which extend the words
+    /// matching the input.
+    pub fn completion_limit(&self) -> usize {
+        self.completion_limit
+    }
+
+    /// Sets the number of the words FST nodes kept for each position of the
+    /// input, when following all the ways to split it into patterns.
+    ///
+    /// The nodes with the best words below them are kept. A wider beam finds
+    /// more words of long and ambiguous inputs, while a narrower one is
+    /// faster. A width of `0` is taken as `1`.
+    pub fn set_beam_width(&mut self, width: usize) {
+        self.beam_width = width.max(1);
+    }
+
+    /// Returns the number of the words FST nodes kept for each position of
+    /// the input.
+    pub fn beam_width(&self) -> usize {
+        self.beam_width
+    }
+
+    /// Sets how the characters of the input which don't match any pattern
+    /// are handled. By default, the words only match the input up to the
+    /// first of them.
+    ///
+    /// The byte ranges of the unmatched characters in the input are reported
+    /// in the [`Suggestion::unmatched`] of the dictionary words.
+    pub fn set_unmatched(&mut self, unmatched: Unmatched) {
+        self.unmatched = unmatched;
+    }
+
+    /// Returns how the characters of the input which don't match any pattern
+    /// are handled.
+    pub fn unmatched(&self) -> Unmatched {
+        self.unmatched
+    }
+
+    /// Returns the dictionary words matching the Avro `input`, ordered by
+    /// the user's previous selections and then by descending word weight.
+    /// The embedded words have no weights, so they are ordered
+    /// lexicographically.
+    ///
+    /// The autocorrect word of the input comes first, then the phonetic
+    /// conversion of the input if [enabled](Suggest::set_include_conversion).
+    /// The autocorrect words of up to three longer inputs starting with the
+    /// input, if it has at least three characters, come after the words
+    /// matching the input, before their completions.
+    ///
+    /// The punctuation before and after the word in the input, like in
+    /// `(ami),`, is converted into Bangla and kept around the suggested words.
+    /// Input with only punctuation suggests its conversion.
+    ///
+    /// If [enabled](Suggest::set_include_english), the input itself comes
+    /// second if it is an English word, with its punctuation unchanged.
+    pub fn suggest(&self, input: &str) -> Vec<String> {
+        let raw_input = self.split_input(input);
+        let segments = self.segments(raw_input.word());
+
+        let builtin = self.candidates(&self.words, &segments, false);
+        let user = self.candidates(self.user_dictionary.tree(), &segments, false);
+
+        let words = self.candidate_words(&fix_string(raw_input.word()), &builtin, &user);
+        self.arrange(&raw_input, words)
+    }
+
+    /// Returns the dictionary words matching the Avro `input` along with their
+    /// score and the details of how they were produced, ordered by descending
+    /// score.
+    ///
+    /// The autocorrect word and the phonetic conversion come first as with
+    /// [`Suggest::suggest`], with an infinite score. The punctuation of the
+    /// input is kept too, and the English word comes second with the score of
+    /// the first suggestion.
+    pub fn suggest_detailed(&self, input: &str) -> Vec<Suggestion> {
+        let raw_input = self.split_input(input);
+        let input = fix_string(raw_input.word());
+        let segments = self.segments(raw_input.word());
+
+        let builtin = self.candidates(&self.words, &segments, true);
+        let user = self.candidates(self.user_dictionary.tree(), &segments, true);
+        let mut completions = self.completions(&input, &builtin, &user);
+
+        let mut suggestions: Vec<_> = builtin
+            .into_iter()
+            .filter_map(Candidate::into_suggestion)
+            .chain(user.into_iter().filter_map(|c| {
+                let mut suggestion = c.into_suggestion()?;
+                suggestion.source = Source::UserDictionary;
+                Some(suggestion)
+            }))
+            .collect();
+
+        // The selected words are ranked above all the others
+        for (word, score) in self.history.scores(&input) {
+            for suggestion in suggestions
+                .iter_mut()
+                .chain(&mut completions)
+                .filter(|s| s.word == word)
+            {
+                suggestion.score += score * (MAX_WEIGHT as f64 + 1.0);
+            }
+        }
+
+        // The sort is stable, so the completions of the same score stay
+        // ordered by their length
+        let mut suggestions = rank(suggestions);
+        suggestions.extend(completions);
+        suggestions.sort_by(|a, b| b.score.total_cmp(&a.score));
+
+        let offset = raw_input.word_offset();
+        for suggestion in &mut suggestions {
+            suggestion.unmatched = segments
+                .unmatched
+                .iter()
+                .map(|range| range.start + offset..range.end + offset)
+                .collect();
+        }
+
+        for (word, source) in self.leading_words(&raw_input, &input).into_iter().rev() {
+            suggestions.retain(|s| s.word != word);
+            suggestions.insert(
+                0,
+                Suggestion {
+                    word,
+                    score: f64::INFINITY,
+                    source,
+                    keys: Vec::new(),
+                    unmatched: Vec::new(),
+                },
+            );
+        }
+
+        if raw_input.word.is_empty() && !raw_input.prefix.is_empty() {
+            return vec![Suggestion {
+                word: raw_input.prefix,
+                score: f64::INFINITY,
+                source: Source::Conversion,
+                keys: Vec::new(),
+                unmatched: Vec::new(),
+            }];
+        }
+
+        for suggestion in &mut suggestions {
+            suggestion.word = raw_input.punctuate(&suggestion.word);
+        }
+
+        if self.is_english(&raw_input) {
+            let index = suggestions.len().min(1);
+            suggestions.insert(
+                index,
+                Suggestion {
+                    word: raw_input.text.to_string(),
+                    score: suggestions.first().map_or(0.0, |s| s.score),
+                    source: Source::English,
+                    keys: Vec::new(),
+                    unmatched: Vec::new(),
+                },
+            );
+        }
+
+        suggestions
+    }
+
+    /// Returns the `k` best dictionary words matching the Avro `input`.
+    ///
+    /// The result is the same as the first `k` words of [`Suggest::suggest`].
+    /// For the words of six letters or more, the best words are searched for
+    /// and the search stops as soon as the `k` best ones are found. The
+    /// shorter words match few dictionary words, so all of them are collected
+    /// and sorted instead, which is faster. They are also all collected when
+    /// the user selected words for the input, which may be any of the words.
+    pub fn suggest_top_k(&self, input: &str, k: usize) -> Vec<String> {
+        let raw_input = self.split_input(input);
+        let segments = self.segments(raw_input.word());
+
+        let words = match self.best_matching_words(&raw_input, &segments, k) {
+            Some(words) => words,
+            None => {
+                let builtin = self.candidates(&self.words, &segments, false);
+                let user = self.candidates(self.user_dictionary.tree(), &segments, false);
+                self.candidate_words(&fix_string(raw_input.word()), &builtin, &user)
+            }
+        };
+
+        let mut words = self.arrange(&raw_input, words);
+        words.truncate(k);
+        words
+    }
+
+    /// Returns the ranked `k` best words of the built-in words and the user
+    /// dictionary matching the `segments` of the `input`, followed by their
+    /// completions if there are fewer, or `None` if all the words are needed
+    /// for the best ones or are collected faster.
+    fn best_matching_words(
+        &self,
+        input: &Input,
+        segments: &Segments<'_>,
+        k: usize,
+    ) -> Option<Vec<String>> {
+        let fixed = fix_string(input.word());
+        if fixed.chars().count() < MIN_SEARCH_LEN || !self.history.scores(&fixed).is_empty() {
+            return None;
+        }
+
+        let (builtin, builtin_ends) = self.best_words(&self.words, segments, k);
+        let (user, user_ends) = self.best_words(self.user_dictionary.tree(), segments, k);
+        let mut words = ranked_words(builtin.into_iter().chain(user));
+
+        // All the words matching the input are found, so the candidates at
+        // the end of the input are all found too, and the completions come
+        // after the words
+        if words.len() < k {
+            let completions = self.completions(&fixed, &builtin_ends, &user_ends);
+            words.extend(completions.into_iter().map(|s| s.word));
+        }
+
+        Some(words)
+    }
+
+    /// Returns the ranked words of the `builtin` and `user` candidates,
+    /// followed by the completions of the normalized `input`.
+    pub(super) fn candidate_words(
+        &self,
+        input: &str,
+        builtin: &[Candidate<'_, '_, FstData>],
+        user: &[Candidate<'_, '_, Vec<u8>>],
+    ) -> Vec<String> {
+        // Only the words are cloned, not the nodes leading to them
+        let mut words = ranked_words(
+            builtin
+                .iter()
+                .filter(|c| c.node.is_word())
+                .filter_map(|c| c.node.clone().get_weighted_word())
+                .chain(
+                    user.iter()
+                        .filter(|c| c.node.is_word())
+                        .filter_map(|c| c.node.clone().get_weighted_word()),
+                ),
+        );
+
+        words.extend(
+            self.completions(input, builtin, user)
+                .into_iter()
+                .map(|s| s.word),
+        );
+        words
+    }
+
+    /// Returns the completions of the normalized `input`: the autocorrect
+    /// words of the longer inputs starting with it, and then the best words
+    /// extending the words of the `builtin` and `user` candidates, up to the
+    /// completion limit.
+    ///
+    /// They are scored below all the words of the candidates themselves. The
+    /// autocorrect words are scored like the heaviest completions, and the
+    /// shortest of the same score come first.
+    fn completions(
+        &self,
+        input: &str,
+        builtin: &[Candidate<'_, '_, FstData>],
+        user: &[Candidate<'_, '_, Vec<u8>>],
+    ) -> Vec<Suggestion> {
+        let matched: HashSet<String> = builtin
+            .iter()
+            .filter_map(|c| c.node.clone().get_word())
+            .chain(user.iter().filter_map(|c| c.node.clone().get_word()))
+            .collect();
+
+        let mut autocorrect: Vec<Suggestion> = self
+            .autocorrect
+            .completions(input)
+            .into_iter()
+            .filter(|word| !matched.contains(*word))
+            .map(|word| Suggestion {
+                word: word.to_string(),
+                // Like a completion of the highest weight
+                score: -1.0,
+                source: Source::Autocorrect,
+                keys: Vec::new(),
+                unmatched: Vec::new(),
+            })
+            .collect();
+
+        if self.completion_limit == 0 {
+            return autocorrect;
+        }
+        // Enough for the limit even if all the matched words are found again
+        let k = self.completion_limit + matched.len();
+
+        let builtin_words = completions(builtin, k).into_iter().map(|w| (w, false));
+        let user_words = completions(user, k).into_iter().map(|w| (w, true));
+
+        let mut suggestions: Vec<Suggestion> = builtin_words
+            .chain(user_words)
+            .filter(|((word, _), _)| !matched.contains(word))
+            .map(|((word, weight), in_user)| Suggestion {
+                keys: if in_user {
+                    keys_of(user, &word)
+                } else {
+                    keys_of(builtin, &word)
+                },
+                unmatched: Vec::new(),
+                word,
+                score: weight as f64 - (MAX_WEIGHT as f64 + 1.0),
+                source: Source::Completion,
+            })
+            .collect();
+        // Like the search, the shortest completions of the same score first
+        suggestions.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then(a.word.len().cmp(&b.word.len()))
+                .then(a.word.cmp(&b.word))
+        });
+        let mut seen: HashSet<String> = autocorrect.iter().map(|s| s.word.clone()).collect();
+        suggestions.retain(|s| seen.insert(s.word.clone()));
+        suggestions.truncate(self.completion_limit);
+
+        autocorrect.extend(suggestions);
+        autocorrect
+    }
+
+    /// Arranges the ranked dictionary `words` of the `input` into the
+    /// suggestions: the selected words first, after the leading words, with
+    /// the punctuation of the input and the English word.
+    pub(super) fn arrange(&self, input: &Input, words: Vec<String>) -> Vec<String> {
+        let fixed = fix_string(input.word());
+        let words = self.prefer_selected(&fixed, words);
+        let mut words = input.punctuate_all(self.with_leading_words(input, &fixed, words));
+        self.insert_english(input, &mut words);
+        words
+    }
+
+    /// Splits the Avro `input` into the word and the punctuation around it.
+    pub(super) fn split_input<'i>(&self, input: &'i str) -> Input<'i> {
+        let text = input.trim();
+        let word = word_range(text);
+
+        Input {
+            text,
+            offset: input.len() - input.trim_start().len(),
+            prefix: self.phonetic.convert_range(text, 0..word.start),
+            suffix: self.phonetic.convert_range(text, word.end..text.len()),
+            word,
+        }
+    }
+
+    /// Returns the words suggested before the dictionary words for the
+    /// `input`, whose word is normalized as `fixed`: the autocorrect word, and
+    /// the phonetic conversion if enabled.
+    fn leading_words(&self, input: &Input, fixed: &str) -> Vec<(String, Source)> {
+        let mut words: Vec<(String, Source)> = self
+            .autocorrect
+            .word(fixed)
+            .map(|word| (word.to_string(), Source::Autocorrect))
+            .into_iter()
+            .collect();
+
+        if self.include_conversion {
+            let conversion = self.phonetic.convert_range(input.text, input.word.clone());
+
+            if !conversion.is_empty() && words.iter().all(|(word, _)| *word != conversion) {
+                words.push((conversion, Source::Conversion));
+            }
+        }
+
+        words
+    }
+
+    /// Puts the leading words of the `input` before the `words`, removing them
+    /// from the rest.
+    fn with_leading_words(&self, input: &Input, fixed: &str, words: Vec<String>) -> Vec<String> {
+        let leading: Vec<String> = self
+            .leading_words(input, fixed)
+            .into_iter()
+            .map(|(word, _)| word)
+            .collect();
+
+        let rest = words.into_iter().filter(|word| !leading.contains(word));
+        leading.iter().cloned().chain(rest).collect()
+    }
+
+    /// Whether the word of the `input` is an English word to be suggested.
+    fn is_english(&self, input: &Input) -> bool {
+        let english = match &self.english {
+            Some(english) if self.include_english => english,
+            _ => return false,
+        };
+
+        english
+            .matching_node(&input.word().to_ascii_lowercase())
+            .and_then(|node| node.get_word())
+            .is_some()
+    }
+
+    /// Inserts the `input` itself after the best of the suggested `words`, if
+    /// it is an English word to be suggested.
+    fn insert_english(&self, input: &Input, words: &mut Vec<String>) {
+        if self.is_english(input) {
+            words.insert(words.len().min(1), input.text.to_string());
+        }
+    }
+
+    /// Moves the words the user selected for the normalized `input` to the
+    /// front, keeping the order of the others.
+    fn prefer_selected(&self, input: &str, mut words: Vec<String>) -> Vec<String> {
+        let scores = self.history.scores(input);
+
+        if !scores.is_empty() {
+            let score = |word: &String| scores.get(word.as_str()).copied().unwrap_or_default();
+            words.sort_by(|a, b| score(b).total_cmp(&score(a)));
+        }
+
+        words
+    }
+
+    /// Returns the Avro inputs for which the Bangla `word` is suggested, the
+    /// most natural one first as the canonical input.
+    ///
+    /// The word is split into the transliterations of the patterns in every
+    /// possible way, with the inherent vowels typed as `o`. The inputs are
+    /// ranked by how close their conversion with the phonetic rules is to the
+    /// word, in the case the rules need, like `uccaroN` for `উচ্চারণ`, and then
+    /// by their length. The digits are left out, as they also spell out whole
+    /// numbers. Only the inputs which produce the word again are returned,
+    /// whether or not it is in the dictionary.
+    pub fn reverse(&self, word: &str) -> Vec<String> {
+        let word = normalize(word);
+
+        if word.is_empty() {
+            return Vec::new();
+        }
+
+        let reverse_patterns = self.reverse_patterns.get_or_init(|| {
+            let mut reverse: HashMap<String, Vec<ReverseKey>> = HashMap::new();
+
+            for (key, block) in &self.patterns {
+                // The digits also spell out whole numbers, like `4` for `চার`
+                if key.contains(|c: char| c.is_ascii_digit()) {
+                    continue;
+                }
+
+                let key = key.to_lowercase();
+                for pattern in &block.transliterate {
+                    let (cased, cost) = self.natural_case(&key, pattern);
+                    reverse
+                        .entry(pattern.clone())
+                        .or_default()
+                        .push(ReverseKey {
+                            key: key.clone(),
+                            cased,
+                            cost,
+                        });
+                }
+            }
+
+            reverse
+        });
+
+        // The best inputs for the rest of the word from each byte offset
+        let mut inputs: Vec<Vec<ReverseInput>> = vec![Vec::new(); word.len() + 1];
+        inputs[word.len()].push(ReverseInput::default());
+
+        for (start, _) in word.char_indices().rev() {
+            let rest = &word[start..];
+            let mut found = Vec::new();
+
+            for end in rest
+                .char_indices()
+                .map(|(i, _)| i)
+                .skip(1)
+                .chain([rest.len()])
+            {
+                let next = &inputs[start + end];
+
+                // The inherent vowel between two letters is typed, like in
+                // `kor` for `কর`, as they would be joined otherwise
+                let vowel = match (rest[..end].chars().last(), rest[end..].chars().next()) {
+                    (Some(last), Some(next))
+                        if is_consonant(last) && (is_consonant(next) || next == 'ং') =>
+                    {
+                        "o"
+                    }
+                    _ => "",
+                };
+
+                if let Some(keys) = reverse_patterns.get(&rest[..end]) {
+                    for key in keys {
+                        found.extend(next.iter().map(|input| ReverseInput {
+                            omitted: input.omitted,
+                            cost: input.cost + key.cost,
+                            input: format!("{}{vowel}{}", key.key, input.input),
+                            cased: format!("{}{vowel}{}", key.cased, input.cased),
+                        }));
+                    }
+                }
+
+                // Common suffixes are added without any input, but the inputs
+                // which spell them out are preferred
+                if start > 0 && self.common_suffixes.iter().any(|s| s == &rest[..end]) {
+                    found.extend(next.iter().map(|input| ReverseInput {
+                        omitted: input.omitted + 1,
+                        ..input.clone()
+                    }));
+                }
+            }
+
+            found.sort_by(|a, b| a.rank().cmp(&b.rank()));
+            let mut seen = HashSet::new();
+            found.retain(|input| seen.insert(input.input.clone()));
+            found.truncate(REVERSE_BEAM);
+            inputs[start] = found;
+        }
+
+        let target = FstTree::from_words([(word.as_str(), 0)]);
+        let mut found: Vec<_> = inputs
+            .swap_remove(0)
+            .into_iter()
+            .filter(|input| {
+                let segments = self.segments(&input.input);
+                !self.best_words(&target, &segments, 1).0.is_empty()
+            })
+            .map(|input| {
+                // The context of the whole input, like the conjuncts
+                let distance = distance(&self.phonetic.convert(&input.cased), &word);
+                (distance, input.input)
+            })
+            .collect();
+
+        // The sort is stable, so the inputs of the same distance stay ranked
+        found.sort_by_key(|(distance, _)| *distance);
+        found
+            .into_iter()
+            .map(|(_, input)| input)
+            .take(REVERSE_LIMIT)
+            .collect()
+    }
+
+    /// Returns the case of the pattern `key` whose conversion with the
+    /// phonetic rules is the closest to the `pattern` it transliterates into,
+    /// along with their distance.
+    fn natural_case(&self, key: &str, pattern: &str) -> (String, usize) {
+        // The vowel signs and the other signs are converted after a consonant
+        let (before, converted_before) = match pattern.chars().next() {
+            Some('\u{0981}'..='\u{0983}' | '\u{09BC}'..='\u{09D7}') => ("k", "ক"),
+            _ => ("", ""),
+        };
+        let pattern = format!("{converted_before}{pattern}");
+
+        self.phonetic
+            .case_variants(key)
+            .into_iter()
+            .map(|cased| {
+                let converted = self.phonetic.convert(&format!("{before}{cased}"));
+                let distance = distance(&converted, &pattern);
+                (cased, distance)
+            })
+            .min_by_key(|(_, distance)| *distance)
+            .unwrap()
+    }
+
+    /// Splits the Avro `word` into the pattern blocks to transliterate, in
+    /// all the possible ways.
+    ///
+    /// A lone `` ` `` which isn't part of any pattern only separates the
+    /// segments around it, like in `` k`h `` for `কহ`. Where the word can't be
+    /// split any further, the character which doesn't match any pattern is
+    /// handled as set by [`Suggest::set_unmatched`].
+    ///
+    /// In the [case-sensitive](Suggest::set_case_sensitive) mode, the blocks
+    /// are narrowed down by the uppercase letters of the word.
+    pub(super) fn segments(&self, word: &str) -> Segments<'_> {
+        let input = fix_string(word);
+        // The characters kept by `fix_string()`, in their original case, and
+        // their byte offsets in the word
+        let (offsets, original): (Vec<usize>, String) = word
+            .char_indices()
+            .filter(|&(_, c)| c.is_ascii_alphanumeric() || c == '`')
+            .unzip();
+
+        let mut segments = Vec::new();
+        let mut unmatched: Vec<Range<usize>> = Vec::new();
+        // Whether a split ends at each byte offset of the input
+        let mut reachable = vec![false; input.len() + 1];
+        reachable[0] = true;
+        let mut furthest = 0;
+
+        for start in 0..input.len() {
+            if !reachable[start] {
+                continue;
+            }
+
+            let found = segments.len();
+
+            for key in self.keys.matching_prefixes(&input[start..]) {
+                if let Some((key, texts)) = self.texts.get_key_value(key) {
+                    let block = &self.patterns[key];
+                    let texts = if self.case_sensitive {
+                        narrow(block, texts, &original[start..start + key.len()])
+                    } else {
+                        Cow::Borrowed(texts)
+                    };
+                    segments.push(Segment {
+                        start,
+                        key,
+                        texts,
+                        optional: block.entire_block_optional == Some(true),
+                    });
+                    reachable[start + key.len()] = true;
+                    furthest = furthest.max(start + key.len());
+                }
+            }
+
+            if segments.len() > found {
+                // Only the longest key may be left out, not the shorter ones
+                // it starts with, so "oo" in "cool" isn't two o's left out
+                let longest = segments[found..].iter().map(|s| s.key.len()).max();
+                for segment in &mut segments[found..] {
+                    segment.optional &= Some(segment.key.len()) == longest;
+                }
+                continue;
+            }
+
+            // The normalized characters are all ASCII
+            let key = match INPUT_CHARS.find(&input[start..start + 1]) {
+                Some(i) => &INPUT_CHARS[i..i + 1],
+                None => continue,
+            };
+            let skipped = Block {
+                transliterate: Vec::new(),
+                entire_block_optional: Some(true),
+            };
+
+            let block = if key == "`" {
+                skipped
+            } else if start < furthest {
+                // The other splits go on after this character
+                continue;
+            } else {
+                let offset = offsets[start];
+                let block = match self.unmatched {
+                    Unmatched::LongestPrefix => {
+                        unmatched.push(offset..word.len());
+                        break;
+                    }
+                    Unmatched::Literal => Block {
+                        transliterate: vec![original[start..start + 1].to_string()],
+                        entire_block_optional: None,
+                    },
+                    Unmatched::Skip => skipped,
+                };
+
+                // The unmatched characters in a row are reported together
+                match unmatched.last_mut() {
+                    Some(range) if range.end == offset => range.end = offset + 1,
+                    _ => unmatched.push(offset..offset + 1),
+                }
+
+                block
+            };
+
+            segments.push(Segment {
+                start,
+                key,
+                optional: block.entire_block_optional == Some(true),
+                texts: Cow::Owned(texts_of(&block)),
+            });
+            reachable[start + 1] = true;
+            furthest = furthest.max(start + 1);
+        }
+
+        Segments {
+            end: reachable.iter().rposition(|&r| r).unwrap_or(0),
+            segments,
+            unmatched,
+        }
+    }
+
+    /// Finds the `k` best words of the `words` FST matching the `segments`,
+    /// and the candidates reached at the end of the input.
+    ///
+    /// The words are the same as the best ones of [`Suggest::candidates`],
+    /// as the nodes at each position of the input are admitted to the beam
+    /// in the same order. If fewer than `k` words are found, the candidates
+    /// are the same as well.
+    fn best_words<'a, 'p, D: AsRef<[u8]>>(
+        &self,
+        words: &'a FstTree<D>,
+        segments: &Segments<'_>,
+        k: usize,
+    ) -> (Vec<(String, u64)>, Vec<Candidate<'a, 'p, D>>) {
+        let mut ends = Vec::new();
+
+        if segments.end == 0 {
+            return (Vec::new(), ends);
+        }
+
+        // The number of nodes admitted to the beam at each position, the word
+        // of the last one, and whether it was expanded with and without the
+        // common suffixes. The nodes come out of the search in the order of
+        // their best words, so a node reached again is the last one.
+        let mut beams: Vec<(usize, String, [bool; 2])> =
+            vec![(0, String::new(), [false; 2]); segments.end + 1];
+
+        let start = words.matching_node("").map(|node| (node, (0, false)));
+
+        // The state of a node is the position in the input it was reached at,
+        // and whether a common suffix can still be added there. The nodes are
+        // expanded in the order of their best words, so the first ones at a
+        // position are the best ones.
+        let words = best_words(start, k, |node, &(position, suffixable), next| {
+            let (admitted, last, expanded) = &mut beams[position];
+            if *admitted == 0 || last != node.word() {
+                if *admitted == self.beam_width {
+                    return false;
+                }
+                *admitted += 1;
+                last.clear();
+                last.push_str(node.word());
+                *expanded = [false; 2];
+            }
+
+            // Like the candidates, a node reached in several ways is followed
+            // once
+            if std::mem::replace(&mut expanded[suffixable as usize], true) {
+                return false;
+            }
+
+            if suffixable {
+                next.extend(
+                    node.matching_nodes(&self.suffixes)
+                        .into_iter()
+                        .map(|(_, node)| (node, (position, false))),
+                );
+            }
+
+            for segment in segments.starting_at(position) {
+                next.extend(
+                    node.matching_nodes(&segment.texts)
+                        .into_iter()
+                        .map(|(_, node)| (node, (segment.end(), true))),
+                );
+
+                if segment.optional {
+                    next.push((node.clone(), (segment.end(), true)));
+                }
+            }
+
+            if position == segments.end {
+                ends.push(Candidate {
+                    node: node.clone(),
+                    keys: None,
+                    source: Source::Pattern,
+                });
+            }
+
+            position == segments.end
+        });
+
+        (words, ends)
+    }
+
+    /// Follows the `segments` in the `words` FST, returning the candidates
+    /// at the end of the input. The pattern keys of the candidates are only
+    /// kept `with_keys`.
+    fn candidates<'a, 'p, D: AsRef<[u8]>>(
+        &self,
+        words: &'a FstTree<D>,
+        segments: &Segments<'p>,
+        with_keys: bool,
+    ) -> Vec<Candidate<'a, 'p, D>> {
+        if segments.end == 0 {
+            return Vec::new();
+        }
+
+        let mut frontiers = vec![start_candidates(words, with_keys)];
+
+        for end in 1..=segments.end {
+            let candidates = self.candidates_at(&frontiers, segments, end);
+            frontiers.push(candidates);
+        }
+
+        frontiers.pop().unwrap_or_default()
+    }
+
+    /// Returns the candidates at the byte offset `end` of the input, by
+    /// following the segments ending there from the candidates at their
+    /// starts, in the `frontiers`.
+    ///
+    /// A node reached in several ways is kept once, with the keys of the
+    /// first way, and only the candidates of the best nodes fit in the beam.
+    pub(super) fn candidates_at<'a, 'p, D: AsRef<[u8]>>(
+        &self,
+        frontiers: &[Vec<Candidate<'a, 'p, D>>],
+        segments: &Segments<'p>,
+        end: usize,
+    ) -> Vec<Candidate<'a, 'p, D>> {
+        let mut candidates = Vec::new();
+
+        for segment in segments.ending_at(end) {
+            let previous = &frontiers[segment.start];
+
+            // Ordered by the transliterations, and then by the previous
+            // candidates
+            let mut found: Vec<_> = previous
+                .iter()
+                .flat_map(|c| {
+                    c.node
+                        .matching_nodes(&segment.texts)
+                        .into_iter()
+                        .map(move |(index, node)| (index, c.with_pattern(segment.key, node)))
+                })
+                .collect();
+            found.sort_by_key(|&(index, _)| index);
+            candidates.extend(found.into_iter().map(|(_, candidate)| candidate));
+
+            if segment.optional {
+                // Entirely optional patterns like "([ওোঅ]|(অ্য)|(য়ো?))?" may not yield any result
+                candidates.extend(previous.iter().cloned());
+            }
+        }
+
+        self.add_common_suffixes(&mut candidates);
+        dedup(&mut candidates);
+
+        self.prune(&mut candidates);
+        candidates
+    }
+
+    /// Keeps the candidates of the `beam_width` nodes with the best words
+    /// below them, the lexicographically smaller first among the same weight.
+    fn prune<D: AsRef<[u8]>>(&self, candidates: &mut Vec<Candidate<'_, '_, D>>) {
+        let mut nodes: Vec<_> = candidates
+            .iter()
+            .map(|c| (Reverse(c.node.best_weight()), c.node.word()))
+            .collect();
+
+        if nodes.len() <= self.beam_width {
+            return;
+        }
+
+        nodes.sort_unstable();
+        nodes.dedup();
+
+        if let Some(&(weight, word)) = nodes.get(self.beam_width - 1) {
+            let last = (weight, word.to_string());
+            candidates.retain(|c| {
+                (Reverse(c.node.best_weight()), c.node.word()) <= (last.0, last.1.as_str())
+            });
+        }
+    }
+
+    fn add_common_suffixes<D: AsRef<[u8]>>(&self, candidates: &mut Vec<Candidate<'_, '_, D>>) {
+        let additional = candidates
+            .iter()
+            .flat_map(|c| {
+                c.node
+                    .matching_nodes(&self.suffixes)
+                    .into_iter()
+                    .map(|(_, node)| c.with_suffix(node))
+            })
+            .collect::<Vec<_>>();
+
+        candidates.extend(additional);
+    }
+}
+
+/// Keeps the first of the `candidates` reaching each node from each source.
+fn dedup<D: AsRef<[u8]>>(candidates: &mut Vec<Candidate<'_, '_, D>>) {
+    // Sorting the few candidates is faster than hashing their words
+    let mut order: Vec<usize> = (0..candidates.len()).collect();
+    let key = |i: usize| {
+        (
+            candidates[i].node.word(),
+            candidates[i].source == Source::CommonSuffix,
+        )
+    };
+    order.sort_unstable_by(|&a, &b| key(a).cmp(&key(b)).then(a.cmp(&b)));
+
+    let mut first = vec![false; candidates.len()];
+    for (n, &i) in order.iter().enumerate() {
+        first[i] = n == 0 || key(order[n - 1]) != key(i);
+    }
+
+    let mut first = first.into_iter();
+    candidates.retain(|_| first.next() == Some(true));
+}
+
+/// Finds the `k` best words below the nodes of the `candidates`, excluding
+/// the words of the nodes themselves.
+fn completions<D: AsRef<[u8]>>(
+    candidates: &[Candidate<'_, '_, D>],
+    k: usize,
+) -> Vec<(String, u64)> {
+    // The same node may be reached through different patterns
+    let mut seen = HashSet::new();
+    let start = candidates
+        .iter()
+        .filter(|c| seen.insert(c.node.word()))
+        .map(|c| (c.node.clone(), false));
+
+    best_words_shortest_first(start, k, |node, &below, next| {
+        next.extend(node.children().map(|child| (child, true)));
+        below
+    })
+}
+
+/// Returns the pattern keys of the longest of the `candidates` which the
+/// completed `word` extends.
+fn keys_of<D: AsRef<[u8]>>(candidates: &[Candidate<'_, '_, D>], word: &str) -> Vec<String> {
+    candidates
+        .iter()
+        .filter(|c| word.starts_with(c.node.word()))
+        .max_by_key(|c| c.node.word().len())
+        .map_or_else(Vec::new, |c| {
+            c.keys
+                .iter()
+                .flatten()
+                .map(|&key| key.to_string())
+                .collect()
+        })
+}
+
+/// The letters which the uppercase Avro letters stand for in the
+/// case-sensitive mode, the longer ones first.
+const CASE_HINTS: [(&str, char); 12] = [
+    ("NG", '\u{099E}'),
+    ("Ng", '\u{0999}'),
+    ("Sh", '\u{09B7}'),
+    ("Th", '\u{09A0}'),
+    ("Dh", '\u{09A2}'),
+    ("Rh", '\u{09DD}'),
+    ("T", '\u{099F}'),
+    ("D", '\u{09A1}'),
+    ("N", '\u{09A3}'),
+    ("R", '\u{09DC}'),
+    ("S", '\u{09B6}'),
+    ("J", '\u{099C}'),
+];
+
+/// Narrows the `texts` of the `block` down to the transliterations with the
+/// letters of the uppercase Avro letters in its `text`, like `ট` for `T`.
+fn narrow<'p>(block: &Block, texts: &'p TextSet, text: &str) -> Cow<'p, TextSet> {
+    let mut letters = Vec::new();
+    let mut rest = text;
+
+    while let Some(c) = rest.chars().next() {
+        match CASE_HINTS.iter().find(|(hint, _)| rest.starts_with(hint)) {
+            Some((hint, letter)) => {
+                letters.push(*letter);
+                rest = &rest[hint.len()..];
+            }
+            None => rest = &rest[c.len_utf8()..],
+        }
+    }
+
+    if letters.is_empty() {
+        return Cow::Borrowed(texts);
+    }
+
+    Cow::Owned(TextSet::new(
+        block
+            .transliterate
+            .iter()
+            .map(String::as_str)
+            .filter(|pattern| letters.iter().all(|&letter| pattern.contains(letter))),
+    ))
+}
+
+/// Returns the transliterations of the `block`, for following them all at
+/// once.
+fn texts_of(block: &Block) -> TextSet {
+    TextSet::new(block.transliterate.iter().map(String::as_str))
+}
+
+/// The ways to split an Avro word into pattern segments.
+pub(super) struct Segments<'p> {
+    /// The segments, ordered by their start.
+    segments: Vec<Segment<'p>>,
+    /// The length of the part of the word which can be split.
+    pub end: usize,
+    /// The byte ranges of the characters of the word which don't match any
+    /// pattern, or of the rest of the word which can't be split.
+    pub unmatched: Vec<Range<usize>>,
+}
+
+impl<'p> Segments<'p> {
+    /// The segments starting at the byte offset `start` of the word.
+    fn starting_at(&self, start: usize) -> &[Segment<'p>] {
+        let first = self.segments.partition_point(|s| s.start < start);
+        let last = self.segments.partition_point(|s| s.start <= start);
+        &self.segments[first..last]
+    }
+
+    /// The segments ending at the byte offset `end` of the word, the longest
+    /// first.
+    fn ending_at(&self, end: usize) -> impl Iterator<Item = &Segment<'p>> {
+        self.segments.iter().filter(move |s| s.end() == end)
+    }
+
+    /// Returns the length of the prefix of the word which is split in the
+    /// same ways in both, up to which the words FST nodes reached are the
+    /// same.
+    pub fn unchanged_len(&self, other: &Segments<'_>) -> usize {
+        let missing = |a: &'_ Segments<'_>, b: &'_ Segments<'_>| {
+            a.segments
+                .iter()
+                .filter(|s| !b.starting_at(s.start).contains(s))
+                .map(Segment::end)
+                .min()
+                .unwrap_or(usize::MAX)
+        };
+
+        // A different segment only changes the nodes from its end
+        let changed = missing(self, other).min(missing(other, self));
+        (changed - 1).min(self.end).min(other.end)
+    }
+}
+
+/// A block of the input to transliterate, with the key of its pattern.
+pub(super) struct Segment<'p> {
+    /// The byte offset of the segment in the word.
+    pub start: usize,
+    pub key: &'p str,
+    /// The transliterations of the pattern, narrowed down in the
+    /// case-sensitive mode.
+    pub texts: Cow<'p, TextSet>,
+    /// Whether the segment may not yield any text.
+    pub optional: bool,
+}
+
+impl PartialEq for Segment<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        // The transliterations of the same pattern are shared, unless they
+        // are narrowed down
+        let same_texts = match (&self.texts, &other.texts) {
+            (Cow::Borrowed(a), Cow::Borrowed(b)) => std::ptr::eq(*a, *b),
+            (a, b) => a == b,
+        };
+
+        self.start == other.start
+            && self.key == other.key
+            && self.optional == other.optional
+            && same_texts
+    }
+}
+
+impl Segment<'_> {
+    /// The byte offset of the end of the segment in the word.
+    fn end(&self) -> usize {
+        self.start + self.key.len()
+    }
+}
+
+/// Returns the candidate of the root of the `words` FST, where all the words
+/// start. The pattern keys of the candidates following it are only kept
+/// `with_keys`.
+pub(super) fn start_candidates<'a, 'p, D: AsRef<[u8]>>(
+    words: &'a FstTree<D>,
+    with_keys: bool,
+) -> Vec<Candidate<'a, 'p, D>> {
+    words
+        .matching_node("")
+        .map(|node| Candidate {
+            node,
+            keys: with_keys.then(Vec::new),
+            source: Source::Pattern,
+        })
+        .into_iter()
+        .collect()
+}
+
+/// A pattern key transliterating into a text, for the reverse
+/// transliterations.
+struct ReverseKey {
+    key: String,
+    /// The key in the case which the phonetic rules convert into the text.
+    cased: String,
+    /// The distance between the phonetic conversion of the key and the text.
+    cost: usize,
+}
+
+/// An Avro input for the rest of a word, in a reverse transliteration.
+#[derive(Clone, Default)]
+struct ReverseInput {
+    /// The number of the common suffixes of the word left out of the input.
+    omitted: usize,
+    /// The sum of the costs of the keys.
+    cost: usize,
+    input: String,
+    cased: String,
+}
+
+impl ReverseInput {
+    /// The inputs needing fewer uppercase letters for the phonetic rules are
+    /// preferred, as the suggestions ignore the case.
+    fn rank(&self) -> (usize, usize, usize, usize, &str) {
+        let uppercase = self.cased.chars().filter(char::is_ascii_uppercase).count();
+        (
+            self.cost,
+            uppercase,
+            self.omitted,
+            self.input.len(),
+            &self.input,
+        )
+    }
+}
+
+/// Whether `c` is a Bangla consonant letter, with an inherent vowel.
+fn is_consonant(c: char) -> bool {
+    matches!(
+        c,
+        '\u{0995}'..='\u{09B9}' | '\u{09DC}' | '\u{09DD}' | '\u{09DF}'
+    )
+}
+
+/// A node of the words FST reached by following the input patterns.
+pub(super) struct Candidate<'a, 'p, D: AsRef<[u8]>> {
+    pub node: FstNode<'a, D>,
+    /// The pattern keys followed to the node, if they are kept.
+    keys: Option<Vec<&'p str>>,
+    source: Source,
+}
+
+impl<D: AsRef<[u8]>> Clone for Candidate<'_, '_, D> {
+    fn clone(&self) -> Self {
+        Candidate {
+            node: self.node.clone(),
+            keys: self.keys.clone(),
+            source: self.source,
+        }
+    }
+}
+
+impl<'a, 'p, D: AsRef<[u8]>> Candidate<'a, 'p, D> {
+    /// The candidate of the `node` reached by following the pattern `key`.
+    fn with_pattern(&self, key: &'p str, node: FstNode<'a, D>) -> Self {
+        let keys = self.keys.as_ref().map(|keys| {
+            let mut keys = keys.clone();
+            keys.push(key);
+            keys
+        });
+
+        Candidate {
+            node,
+            keys,
+            source: self.source,
+        }
+    }
+
+    /// The candidate of the `node` reached by adding a common suffix.
+    fn with_suffix(&self, node: FstNode<'a, D>) -> Self {
+        Candidate {
+            node,
+            keys: self.keys.clone(),
+            source: Source::CommonSuffix,
+        }
+    }
+
+    fn into_suggestion(self) -> Option<Suggestion> {
+        let (word, weight) = self.node.get_weighted_word()?;
+
+        Some(Suggestion {
+            word,
+            score: weight as f64,
+            source: self.source,
+            keys: self.keys.into_iter().flatten().map(String::from).collect(),
+            unmatched: Vec::new(),
+        })
+    }
+}
+
+#[cfg(feature = "embedded")]
+impl Default for Suggest {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Builds a [`Suggest`] from a word list and pattern tables loaded at runtime.
+///
+/// Each of them can be given either as bytes or as a file to read. The
+/// embedded data is used for those not given, if the `embedded` feature is
+/// enabled.
+#[derive(Default)]
+pub struct SuggestBuilder {
+    words: Option<Resource>,
+    patterns: Option<Patterns>,
+    common_suffixes: Option<Resource>,
+    phonetic_rules: Option<Resource>,
+    autocorrect: Option<Resource>,
+    english_words: Option<Resource>,
+}
+
+/// The pattern table, with its transliterations either expanded or written
+/// as regexes.
+enum Patterns {
+    Expanded(Resource),
+    Regex(Resource),
+}
+
+enum Resource {
+    Bytes(Vec<u8>),
+    File(PathBuf),
+    #[cfg(feature = "mmap")]
+    Mapped(PathBuf),
+}
+
+impl Resource {
+    fn read(self) -> io::Result<Vec<u8>> {
+        match self {
+            Resource::Bytes(bytes) => Ok(bytes),
+            Resource::File(path) => fs::read(path),
+            #[cfg(feature = "mmap")]
+            Resource::Mapped(path) => fs::read(path),
+        }
+    }
+
+    fn into_fst_data(self) -> io::Result<FstData> {
+        match self {
+            #[cfg(feature = "mmap")]
+            Resource::Mapped(path) => {
+                let file = fs::File::open(path)?;
+                // SAFETY: The caller of `words_fst_mmap()` guarantees that the
+                // file is not modified while it is mapped.
+                let map = unsafe { memmap2::Mmap::map(&file)? };
+                Ok(FstData::Mapped(map))
+            }
+            resource => Ok(FstData::Owned(resource.read()?)),
+        }
+    }
+}
+
+impl SuggestBuilder {
+    /// Sets the words FST, as generated by the `generate` tool.
+    pub fn words_fst(mut self, bytes: impl Into<Vec<u8>>) -> Self {
+        self.words = Some(Resource::Bytes(bytes.into()));
+        self
+    }
+
+    /// Sets the file to read the words FST from.
+    pub fn words_fst_file(mut self, path: impl AsRef<Path>) -> Self {
+        self.words = Some(Resource::File(path.as_ref().to_path_buf()));
+        self
+    }
+
+    /// Sets the file to memory-map the words FST from, instead of reading it
+    /// into the memory.
+    ///
+    /// The pages of the file are then shared with the other processes mapping
+    /// it, and loaded only when needed.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified or truncated while the built [`Suggest`]
+    /// is alive. See [`memmap2::Mmap::map`] for details.
+    #[cfg(feature = "mmap")]
+    pub unsafe fn words_fst_mmap(mut self, path: impl AsRef<Path>) -> Self {
+        self.words = Some(Resource::Mapped(path.as_ref().to_path_buf()));
+        self
+    }
 
-#[derive(Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct Block {
-    pub transliterate: Vec<String>,
-    pub entire_block_optional: Option<bool>,
-}
+    /// Sets the patterns, in the JSON format of `preprocessed-patterns.json`.
+    pub fn patterns(mut self, json: impl Into<Vec<u8>>) -> Self {
+        self.patterns = Some(Patterns::Expanded(Resource::Bytes(json.into())));
+        self
+    }
 
-pub struct Suggest {
-    patterns: HashMap<String, Block>,
-    common_suffixes: Vec<String>,
-}
+    /// Sets the file to read the patterns from.
+    pub fn patterns_file(mut self, path: impl AsRef<Path>) -> Self {
+        self.patterns = Some(Patterns::Expanded(Resource::File(
+            path.as_ref().to_path_buf(),
+        )));
+        self
+    }
 
-impl Suggest {
-    pub fn new() -> Self {
-        let patterns_data = include_bytes!("../../data/preprocessed-patterns.json");
-        let common_data = include_bytes!("../../data/source-common-patterns.json");
+    /// Sets the patterns, in the JSON format of `source-regex-patterns.json`,
+    /// instead of the expanded ones of [`SuggestBuilder::patterns`].
+    ///
+    /// The regexes are expanded into all the texts they match when building,
+    /// so they can only have literals, groups, alternations, bracketed
+    /// classes and bounded repetitions like `?` or `{1,2}`. The others fail
+    /// with [`Error::UnsupportedRegex`].
+    pub fn regex_patterns(mut self, json: impl Into<Vec<u8>>) -> Self {
+        self.patterns = Some(Patterns::Regex(Resource::Bytes(json.into())));
+        self
+    }
 
-        let patterns: HashMap<String, Block> = serde_json::from_slice(patterns_data).unwrap();
-        let common_suffixes = serde_json::from_slice(common_data).unwrap();
+    /// Sets the file to read the regex patterns from.
+    pub fn regex_patterns_file(mut self, path: impl AsRef<Path>) -> Self {
+        self.patterns = Some(Patterns::Regex(Resource::File(path.as_ref().to_path_buf())));
+        self
+    }
 
-        Suggest {
-            patterns,
-            common_suffixes,
-        }
+    /// Sets the common suffixes, in the JSON format of `source-common-patterns.json`.
+    pub fn common_suffixes(mut self, json: impl Into<Vec<u8>>) -> Self {
+        self.common_suffixes = Some(Resource::Bytes(json.into()));
+        self
     }
 
-    pub fn suggest(&self, input: &str) -> Vec<String> {
-        let words = Lazy::force(&WORDS);
-        let patterns = Lazy::force(&PATTERNS);
-        let input = fix_string(input);
+    /// Sets the file to read the common suffixes from.
+    pub fn common_suffixes_file(mut self, path: impl AsRef<Path>) -> Self {
+        self.common_suffixes = Some(Resource::File(path.as_ref().to_path_buf()));
+        self
+    }
 
-        let (matched, mut remaining, _) = patterns.match_longest_common_prefix(&input);
+    /// Sets the rules of the phonetic conversion, in the JSON format of
+    /// `phonetic-rules.json`.
+    pub fn phonetic_rules(mut self, json: impl Into<Vec<u8>>) -> Self {
+        self.phonetic_rules = Some(Resource::Bytes(json.into()));
+        self
+    }
 
-        let matched_patterns = if let Some(block) = self.patterns.get(matched) {
-            &block.transliterate
-        } else {
-            return vec![];
-        };
+    /// Sets the file to read the rules of the phonetic conversion from.
+    pub fn phonetic_rules_file(mut self, path: impl AsRef<Path>) -> Self {
+        self.phonetic_rules = Some(Resource::File(path.as_ref().to_path_buf()));
+        self
+    }
 
-        let mut matched_nodes = matched_patterns
-            .iter()
-            .filter_map(|p| words.matching_node(p))
-            .collect::<Vec<_>>();
+    /// Sets the autocorrect words of the inputs, in the JSON format of
+    /// `autocorrect.json`, an object of the Bangla words by their inputs.
+    ///
+    /// Without the `embedded` feature, there are no autocorrect words unless
+    /// they are set.
+    pub fn autocorrect(mut self, json: impl Into<Vec<u8>>) -> Self {
+        self.autocorrect = Some(Resource::Bytes(json.into()));
+        self
+    }
 
-        let additional_nodes = matched_nodes
-            .iter()
-            .flat_map(|node| {
-                self.common_suffixes
-                    .iter()
-                    .filter_map(|suffix| node.get_matching_node(suffix))
-            })
-            .collect::<Vec<_>>();
+    /// Sets the file to read the autocorrect words from.
+    pub fn autocorrect_file(mut self, path: impl AsRef<Path>) -> Self {
+        self.autocorrect = Some(Resource::File(path.as_ref().to_path_buf()));
+        self
+    }
+
+    /// Sets the FST of the English words, as generated by the `generate` tool.
+    ///
+    /// Without the `embedded` feature, there are no English words unless they
+    /// are set.
+    pub fn english_words_fst(mut self, bytes: impl Into<Vec<u8>>) -> Self {
+        self.english_words = Some(Resource::Bytes(bytes.into()));
+        self
+    }
 
-        matched_nodes.extend(additional_nodes);
+    /// Sets the file to read the FST of the English words from.
+    pub fn english_words_fst_file(mut self, path: impl AsRef<Path>) -> Self {
+        self.english_words = Some(Resource::File(path.as_ref().to_path_buf()));
+        self
+    }
 
-        while !remaining.is_empty() {
-            let (mut new_matched, new_remaining, mut complete) =
-                patterns.match_longest_common_prefix(remaining);
+    /// Loads the data and builds the suggester.
+    ///
+    /// Fails if any of the data can't be read or is invalid.
+    pub fn build(self) -> Result<Suggest, Error> {
+        let words = match self.words {
+            Some(words) => words.into_fst_data()?,
+            None => FstData::Static(embedded::words()?),
+        };
+        let words = FstTree::try_from_fst(words)?;
+        let mut expander = Expander::default();
 
-            if !complete {
-                for i in (0..remaining.len()).rev() {
-                    (new_matched, _, complete) =
-                        patterns.match_longest_common_prefix(&remaining[..i]);
+        let mut patterns: HashMap<String, Block> = match self.patterns {
+            Some(Patterns::Expanded(patterns)) => {
+                let patterns: HashMap<String, Block> =
+                    serde_json::from_slice(&patterns.read()?).map_err(Error::InvalidPatterns)?;
 
-                    if complete {
-                        remaining = &remaining[i..];
-                        break;
-                    }
-                }
-            } else {
-                remaining = new_remaining;
+                patterns
+                    .into_iter()
+                    .map(|(key, block)| {
+                        let block = checked_block(&key, block)?;
+                        Ok((key, block))
+                    })
+                    .collect::<Result<_, Error>>()?
             }
+            Some(Patterns::Regex(patterns)) => {
+                let patterns: RegexPatterns =
+                    serde_json::from_slice(&patterns.read()?).map_err(Error::InvalidPatterns)?;
 
-            let new_matched_patterns = if let Some(block) = self.patterns.get(new_matched) {
-                &block.transliterate
-            } else {
-                // If no patterns match, we can stop here
-                break;
-            };
+                patterns
+                    .into_iter()
+                    .map(|(key, block)| {
+                        let block = checked_block(&key, block.into_block(&key, &mut expander)?)?;
+                        Ok((key, block))
+                    })
+                    .collect::<Result<_, Error>>()?
+            }
+            None => {
+                serde_json::from_slice(embedded::patterns()?).map_err(Error::InvalidPatterns)?
+            }
+        };
 
-            let new_matched_nodes = new_matched_patterns
-                .iter()
-                .flat_map(|p| {
-                    matched_nodes
-                        .iter()
-                        .filter_map(|node| node.get_matching_node(p))
-                })
-                .collect::<Vec<_>>();
+        if let Some((key, _)) = patterns.iter().find(|(_, b)| b.transliterate.is_empty()) {
+            return Err(Error::MissingPattern(key.clone()));
+        }
 
-            if self
-                .patterns
-                .get(new_matched)
-                .map_or(false, |v| v.entire_block_optional.is_some())
-            {
-                // Entirely optional patterns like "([ওোঅ]|(অ্য)|(য়ো?))?" may not yield any result
-                matched_nodes.extend(new_matched_nodes);
-            } else {
-                matched_nodes = new_matched_nodes;
-            }
+        let mut common_suffixes = match self.common_suffixes {
+            Some(suffixes) => serde_json::from_slice(&suffixes.read()?),
+            None => serde_json::from_slice(embedded::common_suffixes()?),
+        }
+        .map_err(Error::InvalidPatterns)?;
 
-            let additional_matched_nodes = matched_nodes
-                .iter()
-                .flat_map(|node| {
-                    self.common_suffixes
-                        .iter()
-                        .filter_map(|suffix| node.get_matching_node(suffix))
-                })
-                .collect::<Vec<_>>();
-            matched_nodes.extend(additional_matched_nodes);
+        let phonetic = match self.phonetic_rules {
+            Some(rules) => serde_json::from_slice(&rules.read()?),
+            None => serde_json::from_slice(embedded::phonetic_rules()?),
         }
+        .map_err(Error::InvalidPhoneticRules)?;
 
-        let suggestions: HashSet<_> = matched_nodes
-            .into_iter()
-            .filter_map(|n| n.get_word())
+        let autocorrect = match self.autocorrect {
+            Some(autocorrect) => serde_json::from_slice(&autocorrect.read()?),
+            None => match embedded::autocorrect() {
+                Some(autocorrect) => serde_json::from_slice(autocorrect),
+                None => Ok(Autocorrect::default()),
+            },
+        }
+        .map_err(Error::InvalidAutocorrect)?;
+
+        let english = match self.english_words {
+            Some(english) => Some(english.into_fst_data()?),
+            None => embedded::english_words().map(FstData::Static),
+        };
+        let english = english.map(FstTree::try_from_fst).transpose()?;
+
+        // Produce the words in the same form as the dictionary
+        for block in patterns.values_mut() {
+            normalize_all(&mut block.transliterate);
+        }
+        normalize_all(&mut common_suffixes);
+
+        let keys = pattern_keys(&patterns);
+        let texts = patterns
+            .iter()
+            .map(|(key, block)| (key.clone(), texts_of(block)))
             .collect();
-        suggestions.into_iter().collect()
+        let suffixes = TextSet::new(common_suffixes.iter().map(String::as_str));
+
+        Ok(Suggest {
+            words,
+            keys,
+            patterns,
+            texts,
+            common_suffixes,
+            suffixes,
+            phonetic,
+            autocorrect,
+            english,
+            include_conversion: false,
+            include_english: false,
+            case_sensitive: false,
+            completion_limit: 0,
+            beam_width: DEFAULT_BEAM_WIDTH,
+            unmatched: Unmatched::default(),
+            user_dictionary: UserDictionary::new(),
+            history: SelectionHistory::new(),
+            expander,
+            reverse_patterns: OnceCell::new(),
+        })
     }
 }
 
-#[cfg(test)]
+/// Builds the FST of the keys of the `patterns`.
+fn pattern_keys(patterns: &HashMap<String, Block>) -> FstTree<Vec<u8>> {
+    FstTree::from_words(patterns.keys().map(|key| (key.as_str(), 0)))
+}
+
+/// Checks the pattern `key` and its `block`, and normalizes the
+/// transliterations of the block.
+fn checked_block(key: &str, mut block: Block) -> Result<Block, Error> {
+    if key.is_empty() || !key.chars().all(|c| INPUT_CHARS.contains(c)) {
+        return Err(Error::InvalidPatternKey(key.to_string()));
+    }
+
+    normalize_all(&mut block.transliterate);
+    block.transliterate.retain(|text| !text.is_empty());
+    if block.transliterate.is_empty() {
+        return Err(Error::MissingPattern(key.to_string()));
+    }
+
+    Ok(block)
+}
+
+/// Normalizes the `texts`, removing the ones which become duplicates.
+fn normalize_all(texts: &mut Vec<String>) {
+    let mut seen = HashSet::new();
+    texts.retain_mut(|text| {
+        *text = normalize(text);
+        seen.insert(text.clone())
+    });
+}
+
+/// The data embedded in the library, if the `embedded` feature is enabled.
+mod embedded {
+    use std::io;
+
+    #[cfg(feature = "embedded")]
+    pub fn words() -> io::Result<&'static [u8]> {
+        Ok(crate::WORDS_FST)
+    }
+
+    #[cfg(feature = "embedded")]
+    pub fn patterns() -> io::Result<&'static [u8]> {
+        Ok(include_bytes!("../../data/preprocessed-patterns.json"))
+    }
+
+    #[cfg(feature = "embedded")]
+    pub fn common_suffixes() -> io::Result<&'static [u8]> {
+        Ok(include_bytes!("../../data/source-common-patterns.json"))
+    }
+
+    #[cfg(feature = "embedded")]
+    pub fn phonetic_rules() -> io::Result<&'static [u8]> {
+        Ok(include_bytes!("../../data/phonetic-rules.json"))
+    }
+
+    /// The autocorrect and English words are optional, unlike the other data.
+    #[cfg(feature = "embedded")]
+    pub fn autocorrect() -> Option<&'static [u8]> {
+        Some(include_bytes!("../../data/autocorrect.json"))
+    }
+
+    #[cfg(feature = "embedded")]
+    pub fn english_words() -> Option<&'static [u8]> {
+        Some(crate::ENGLISH_FST)
+    }
+
+    #[cfg(not(feature = "embedded"))]
+    pub fn words() -> io::Result<&'static [u8]> {
+        Err(missing("words FST"))
+    }
+
+    #[cfg(not(feature = "embedded"))]
+    pub fn patterns() -> io::Result<&'static [u8]> {
+        Err(missing("patterns"))
+    }
+
+    #[cfg(not(feature = "embedded"))]
+    pub fn common_suffixes() -> io::Result<&'static [u8]> {
+        Err(missing("common suffixes"))
+    }
+
+    #[cfg(not(feature = "embedded"))]
+    pub fn phonetic_rules() -> io::Result<&'static [u8]> {
+        Err(missing("phonetic rules"))
+    }
+
+    #[cfg(not(feature = "embedded"))]
+    pub fn autocorrect() -> Option<&'static [u8]> {
+        None
+    }
+
+    #[cfg(not(feature = "embedded"))]
+    pub fn english_words() -> Option<&'static [u8]> {
+        None
+    }
+
+    #[cfg(not(feature = "embedded"))]
+    fn missing(what: &str) -> io::Error {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("The {what} must be given without the `embedded` feature"),
+        )
+    }
+}
+
+#[cfg(all(test, feature = "embedded"))]
 mod tests {
     use super::*;
 
@@ -136,6 +1956,18 @@ mod tests {
         vec
     }
 
+    /// Builds a suggester of the `words` with the `patterns`, without any
+    /// common suffix or autocorrect word.
+    fn suggest_with(words: &FstTree<Vec<u8>>, patterns: &str) -> Suggest {
+        Suggest::builder()
+            .words_fst(words.as_bytes())
+            .patterns(patterns)
+            .common_suffixes("[]")
+            .autocorrect("{}")
+            .build()
+            .unwrap()
+    }
+
     #[test]
     fn test_suggestions() {
         let suggest = Suggest::new();
@@ -184,6 +2016,701 @@ mod tests {
         assert_eq!(sort(suggest.suggest("shesh")), vec!["শেষ", "সেস"]);
     }
 
+    #[test]
+    fn test_detailed_suggestions() {
+        let suggest = Suggest::new();
+        let suggestions = suggest.suggest_detailed("sari");
+
+        assert_eq!(suggestions.len(), 9);
+        assert!(suggestions.windows(2).all(|w| w[0].score >= w[1].score));
+
+        let sari = suggestions.iter().find(|s| s.word == "সারি").unwrap();
+        assert_eq!(sari.source, Source::Pattern);
+        assert_eq!(sari.keys, ["s", "a", "ri"]);
+
+        let smori = suggestions.iter().find(|s| s.word == "স্মঅরী").unwrap();
+        assert_eq!(smori.source, Source::CommonSuffix);
+        assert_eq!(smori.keys, ["s", "a", "ri"]);
+
+        assert_eq!(suggest.suggest_detailed("shesh")[0].keys, ["sh", "e", "sh"]);
+        assert!(suggest.suggest_detailed("6t``").is_empty());
+    }
+
+    #[test]
+    fn test_top_k_suggestions() {
+        let suggest = Suggest::new();
+
+        for input in ["a", "arO", "sari", "bistari", "kkhet", "as", "6t``", "t6th"] {
+            let all = suggest.suggest(input);
+
+            for k in [0, 1, 5, 9, all.len() + 1] {
+                assert_eq!(suggest.suggest_top_k(input, k), all[..k.min(all.len())]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_segmentations() {
+        let words = FstTree::from_words([("কহা", 5), ("খা", 1), ("কা", 0)]);
+        let patterns = r#"{
+            "a": { "transliterate": ["া"] },
+            "h": { "transliterate": ["হ"] },
+            "k": { "transliterate": ["ক"] },
+            "kh": { "transliterate": ["খ"] }
+        }"#;
+
+        let mut suggest = suggest_with(&words, patterns);
+        assert_eq!(suggest.beam_width(), DEFAULT_BEAM_WIDTH);
+
+        // Both `kh` and `k` + `h`
+        assert_eq!(suggest.suggest("kha"), ["কহা", "খা"]);
+        assert_eq!(suggest.suggest_top_k("kha", 1), ["কহা"]);
+        let detailed = suggest.suggest_detailed("kha");
+        assert_eq!(detailed[0].keys, ["k", "h", "a"]);
+        assert_eq!(detailed[1].keys, ["kh", "a"]);
+        assert_eq!(suggest.suggest("k`ha"), ["কহা"]);
+        assert_eq!(suggest.suggest("ka"), ["কা"]);
+
+        // Only the best node after `kh`
+        suggest.set_beam_width(1);
+        assert_eq!(suggest.suggest("kha"), ["কহা"]);
+        assert_eq!(suggest.suggest_top_k("kha", 2), ["কহা"]);
+        assert_eq!(suggest.suggest("ka"), ["কা"]);
+        suggest.set_beam_width(0);
+        assert_eq!(suggest.beam_width(), 1);
+    }
+
+    #[test]
+    #[allow(clippy::single_range_in_vec_init)]
+    fn test_unmatched() {
+        let words = FstTree::from_words([("ক", 0), ("কা", 0), ("কxা", 0), ("কসা", 0)]);
+        let patterns = r#"{
+            "a": { "transliterate": ["া"] },
+            "k": { "transliterate": ["ক"] },
+            "kxa": { "transliterate": ["কসা"] }
+        }"#;
+
+        let mut suggest = suggest_with(&words, patterns);
+        assert_eq!(suggest.unmatched(), Unmatched::LongestPrefix);
+
+        // Only where no other split goes on
+        assert_eq!(suggest.suggest("kxa"), ["কসা"]);
+        assert!(suggest.suggest_detailed("kxa")[0].unmatched.is_empty());
+
+        assert_eq!(suggest.suggest("kxka"), ["ক"]);
+        assert_eq!(suggest.suggest_detailed("kxka")[0].unmatched, [1..4]);
+        assert_eq!(suggest.suggest_detailed(" (kxka")[0].unmatched, [3..6]);
+        assert!(suggest.suggest("xa").is_empty());
+
+        suggest.set_unmatched(Unmatched::Skip);
+        assert_eq!(suggest.suggest("kxxa"), ["কা"]);
+        assert_eq!(suggest.suggest_detailed("kxxa")[0].unmatched, [1..3]);
+        assert_eq!(suggest.suggest_detailed("kx-xa")[0].unmatched, [1..2, 3..4]);
+        assert_eq!(suggest.suggest_top_k("kxxa", 2), ["কা"]);
+        assert_eq!(suggest.suggest("xka"), ["কা"]);
+        assert_eq!(suggest.suggest("kxa"), ["কসা"]);
+
+        suggest.set_unmatched(Unmatched::Literal);
+        assert_eq!(suggest.suggest("kxka"), Vec::<String>::new());
+        assert_eq!(suggest.suggest("kx"), Vec::<String>::new());
+        let detailed = suggest.suggest_detailed("kx`a");
+        assert_eq!(detailed[0].word, "কxা");
+        assert_eq!(detailed[0].keys, ["k", "x", "a"]);
+        assert_eq!(detailed[0].unmatched, [1..2]);
+
+        // The words of the user dictionary with the characters
+        suggest.user_dictionary_mut().add("কxক");
+        assert_eq!(suggest.suggest("kxk"), ["কxক"]);
+        assert_eq!(suggest.suggest_top_k("kxk", 1), ["কxক"]);
+        suggest.set_unmatched(Unmatched::LongestPrefix);
+        assert_eq!(suggest.suggest("kxk"), ["ক"]);
+        suggest.set_unmatched(Unmatched::Literal);
+
+        // The split of `x` changes with the next characters
+        let mut session = suggest.session();
+        for c in "kxa".chars() {
+            session.push_char(c);
+            assert_eq!(session.suggestions(), suggest.suggest(session.input()));
+        }
+        assert_eq!(session.suggestions(), ["কসা"]);
+    }
+
+    #[test]
+    fn test_pattern_overrides() {
+        let mut suggest = Suggest::new();
+        assert!(suggest.suggest("zol").contains(&"জল".to_string()));
+        assert!(suggest.pattern("z").unwrap().transliterate.len() > 1);
+        assert!(suggest.reverse("জল").contains(&"zol".to_string()));
+
+        suggest
+            .override_patterns(r#"{ "z": { "transliterate": "(য)" }, "q": null }"#)
+            .unwrap();
+        assert_eq!(suggest.pattern("z").unwrap().transliterate, ["য"]);
+        assert!(!suggest.suggest("zol").contains(&"জল".to_string()));
+        assert!(suggest.suggest("zodi").contains(&"যদি".to_string()));
+        assert_eq!(suggest.pattern("q"), None);
+        assert!(!suggest.reverse("জল").contains(&"zol".to_string()));
+
+        let bx = Block {
+            transliterate: vec!["বাক্স".to_string(), String::new()],
+            entire_block_optional: None,
+        };
+        assert_eq!(suggest.set_pattern("bx", bx).unwrap(), None);
+        assert_eq!(suggest.pattern("bx").unwrap().transliterate, ["বাক্স"]);
+        assert!(suggest.suggest("bx").contains(&"বাক্স".to_string()));
+        assert_eq!(suggest.suggest_top_k("bx", 2), suggest.suggest("bx")[..2]);
+        assert!(suggest.reverse("বাক্স").contains(&"bx".to_string()));
+
+        // The o in the middle of a word may be left out
+        let mut session = suggest.session();
+        "bxo".chars().for_each(|c| session.push_char(c));
+        assert!(session.suggestions().contains(&"বাক্স".to_string()));
+
+        // Unless the block of the o isn't optional
+        suggest
+            .override_patterns(
+                r#"{ "o": { "transliterate": "([ওোঅ]|(অ্য)|(য়ো?))?", "entireBlockOptional": false } }"#,
+            )
+            .unwrap();
+        assert!(!suggest.suggest("bxo").contains(&"বাক্স".to_string()));
+
+        assert!(suggest.remove_pattern("bx").is_some());
+        assert_eq!(suggest.remove_pattern("bx"), None);
+        assert!(!suggest.suggest("bx").contains(&"বাক্স".to_string()));
+
+        let block = || Block {
+            transliterate: vec!["ক".to_string()],
+            entire_block_optional: None,
+        };
+        assert!(matches!(
+            suggest.set_pattern("Q", block()),
+            Err(Error::InvalidPatternKey(key)) if key == "Q"
+        ));
+        assert!(matches!(
+            suggest.set_pattern("", block()),
+            Err(Error::InvalidPatternKey(_))
+        ));
+        let empty = Block {
+            transliterate: vec![String::new()],
+            entire_block_optional: Some(true),
+        };
+        assert!(matches!(
+            suggest.set_pattern("q", empty),
+            Err(Error::MissingPattern(_))
+        ));
+
+        // Nothing is changed by invalid overrides
+        let overrides = r#"{ "z": { "transliterate": "(জ)" }, "y": { "transliterate": "য+" } }"#;
+        assert!(matches!(
+            suggest.override_patterns(overrides),
+            Err(Error::UnsupportedRegex { .. })
+        ));
+        assert!(matches!(
+            suggest.override_patterns(r#"{ "z": [] }"#),
+            Err(Error::InvalidPatterns(_))
+        ));
+        assert_eq!(suggest.pattern("z").unwrap().transliterate, ["য"]);
+        assert!(matches!(
+            suggest.override_patterns_file("missing.json"),
+            Err(Error::Io(_))
+        ));
+    }
+
+    #[test]
+    fn test_user_dictionary() {
+        let mut suggest = Suggest::new();
+        assert_eq!(suggest.suggest("fesbuk"), Vec::<String>::new());
+
+        suggest.user_dictionary_mut().add("ফেসবুক");
+        suggest.user_dictionary_mut().add("আমরাই");
+
+        assert_eq!(suggest.suggest("fesbuk"), ["ফেসবুক"]);
+        assert_eq!(suggest.suggest_top_k("fesbuk", 1), ["ফেসবুক"]);
+        assert_eq!(
+            sort(suggest.suggest("amrai")),
+            sort(suggest.suggest_top_k("amrai", 10))
+        );
+        assert!(suggest.suggest("amrai").contains(&"আমরাই".to_string()));
+
+        let detailed = suggest.suggest_detailed("fesbuk");
+        assert_eq!(detailed[0].source, Source::UserDictionary);
+
+        suggest.user_dictionary_mut().remove("ফেসবুক");
+        assert_eq!(suggest.suggest("fesbuk"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_selections() {
+        let mut suggest = Suggest::new();
+        let sari = suggest.suggest("sari");
+        assert_eq!(sari[0], "শারি");
+
+        suggest.record_selection("sari", "সারী");
+        suggest.record_selection("sari", "শা\u{9dc}ি");
+        suggest.record_selection("Sari", "শা\u{9dc}ি");
+
+        let ranked = suggest.suggest("sari");
+        assert_eq!(ranked[..3], ["শা\u{9dc}ি", "সারী", "শারি"]);
+        assert_eq!(sort(ranked.clone()), sort(sari));
+        assert_eq!(suggest.suggest_top_k("sari", 2), ranked[..2]);
+        assert_eq!(suggest.suggest_top_k("sari", 10), ranked);
+
+        let detailed = suggest.suggest_detailed("sari");
+        assert_eq!(detailed[0].word, "শা\u{9dc}ি");
+        assert_eq!(detailed[1].word, "সারী");
+
+        // Selections of words which can't be suggested are ignored
+        suggest.record_selection("sari", "আমি");
+        assert_eq!(suggest.suggest_top_k("sari", 10), ranked);
+
+        let history = suggest.selection_history().clone();
+        let mut fresh = Suggest::new();
+        fresh.set_selection_history(history);
+        assert_eq!(fresh.suggest("sari"), ranked);
+
+        // The selections count for similar inputs too
+        assert_eq!(Suggest::new().suggest("shari")[0], "শারি");
+        assert_eq!(fresh.suggest("shari")[..2], ["শা\u{9dc}ি", "সারী"]);
+    }
+
+    #[test]
+    fn test_builder() {
+        let words = FstTree::from_words([("আমি", 1), ("আমরা", 2), ("তুমি", 0)]);
+        let patterns = r#"{
+            "a": { "transliterate": ["আ", "া"] },
+            "i": { "transliterate": ["ি"] },
+            "m": { "transliterate": ["ম"] },
+            "r": { "transliterate": ["র"] }
+        }"#;
+
+        let suggest = suggest_with(&words, patterns);
+        assert_eq!(suggest.suggest("ami"), ["আমি"]);
+        assert_eq!(suggest.suggest("amra"), ["আমরা"]);
+        assert_eq!(suggest.suggest("tumi"), Vec::<String>::new());
+
+        let suggest = Suggest::builder()
+            .words_fst(words.as_bytes())
+            .patterns(patterns)
+            .autocorrect(r#"{ "tumi": "তুমি" }"#)
+            .build()
+            .unwrap();
+        assert_eq!(suggest.suggest("tumi"), ["তুমি"]);
+        assert_eq!(suggest.suggest("ami"), ["আমি"]);
+
+        let suggest = Suggest::builder()
+            .phonetic_rules(
+                r#"{ "vowel": "a", "consonant": "k", "casesensitive": "",
+                    "patterns": [{ "find": "k", "replace": "খ" }] }"#,
+            )
+            .build()
+            .unwrap();
+        assert_eq!(suggest.convert("kk"), "খখ");
+
+        let dir = std::env::temp_dir();
+        let path = |name: &str| {
+            dir.join(format!(
+                "upodesh-builder-test-{}-{name}",
+                std::process::id()
+            ))
+        };
+        let (words_path, patterns_path, suffixes_path) = (
+            path("words.fst"),
+            path("patterns.json"),
+            path("suffixes.json"),
+        );
+        fs::write(&words_path, words.as_bytes()).unwrap();
+        fs::write(&patterns_path, patterns).unwrap();
+        fs::write(&suffixes_path, "[]").unwrap();
+
+        let suggest = Suggest::builder()
+            .words_fst_file(&words_path)
+            .patterns_file(&patterns_path)
+            .common_suffixes_file(&suffixes_path)
+            .build()
+            .unwrap();
+        assert_eq!(suggest.suggest("amra"), ["আমরা"]);
+
+        for path in [words_path, patterns_path, suffixes_path] {
+            fs::remove_file(path).unwrap();
+        }
+
+        #[cfg(feature = "mmap")]
+        {
+            let path = path("mapped-words.fst");
+            fs::write(&path, words.as_bytes()).unwrap();
+
+            let suggest = unsafe { Suggest::builder().words_fst_mmap(&path) }
+                .patterns(patterns)
+                .build()
+                .unwrap();
+            assert_eq!(suggest.suggest("ami"), ["আমি"]);
+
+            drop(suggest);
+            fs::remove_file(path).unwrap();
+        }
+
+        assert!(matches!(
+            Suggest::builder().words_fst(vec![1, 2, 3]).build(),
+            Err(Error::InvalidFst(_))
+        ));
+        assert!(matches!(
+            Suggest::builder().patterns("{").build(),
+            Err(Error::InvalidPatterns(_))
+        ));
+        assert!(matches!(
+            Suggest::builder()
+                .patterns(r#"{ "a": { "transliterate": [] } }"#)
+                .build(),
+            Err(Error::MissingPattern(key)) if key == "a"
+        ));
+        assert!(matches!(
+            Suggest::builder()
+                .patterns(r#"{ "A": { "transliterate": ["আ"] } }"#)
+                .build(),
+            Err(Error::InvalidPatternKey(key)) if key == "A"
+        ));
+        assert!(matches!(
+            Suggest::builder().phonetic_rules("[]").build(),
+            Err(Error::InvalidPhoneticRules(_))
+        ));
+        assert!(matches!(
+            Suggest::builder().autocorrect(r#"{ "ami": 1 }"#).build(),
+            Err(Error::InvalidAutocorrect(_))
+        ));
+        assert!(matches!(
+            Suggest::builder()
+                .common_suffixes_file(dir.join("upodesh-missing-file.json"))
+                .build(),
+            Err(Error::Io(_))
+        ));
+        assert!(Suggest::try_new().is_ok());
+    }
+
+    #[test]
+    fn test_regex_patterns() {
+        let words = FstTree::from_words([("আমি", 1), ("আমরা", 2), ("আম্র", 0)]);
+        let suggest = Suggest::builder()
+            .words_fst(words.as_bytes())
+            .regex_patterns(
+                r#"{ "a": { "transliterate": "(আ|া)" }, "i": { "transliterate": "ি" },
+                    "m": { "transliterate": "ম(্)?" }, "r": { "transliterate": "[র]" } }"#,
+            )
+            .common_suffixes("[]")
+            .build()
+            .unwrap();
+        assert_eq!(suggest.pattern("m").unwrap().transliterate, ["ম", "ম্"]);
+        assert_eq!(suggest.suggest("ami"), ["আমি"]);
+        assert_eq!(suggest.suggest("amr"), ["আম্র"]);
+
+        // The same suggestions as with the patterns expanded by the `generate` tool
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("data/source-regex-patterns.json");
+        let suggest = Suggest::builder()
+            .regex_patterns_file(path)
+            .build()
+            .unwrap();
+        let embedded = Suggest::new();
+        for input in ["ami", "amra", "bangla", "ongshochched", "k`hobor", "Ox"] {
+            assert_eq!(suggest.suggest(input), embedded.suggest(input));
+        }
+
+        let error = Suggest::builder()
+            .regex_patterns(r#"{ "a": { "transliterate": "(আ)*" } }"#)
+            .build()
+            .err()
+            .unwrap();
+        assert_eq!(
+            error.to_string(),
+            r#"Unsupported regex "(আ)*" of the pattern "a": the unbounded repetition `*` isn't supported"#
+        );
+        assert!(matches!(
+            Suggest::builder()
+                .regex_patterns(r#"{ "a": { "transliterate": "(|)" } }"#)
+                .build(),
+            Err(Error::MissingPattern(key)) if key == "a"
+        ));
+        assert!(matches!(
+            Suggest::builder()
+                .regex_patterns(r#"{ "a": { "transliterate": ["আ"] } }"#)
+                .build(),
+            Err(Error::InvalidPatterns(_))
+        ));
+    }
+
+    #[test]
+    fn test_conversion() {
+        let mut suggest = Suggest::new();
+        assert_eq!(suggest.convert("t6th"), "ত৬থ");
+        assert_eq!(suggest.convert(" ami "), "আমি");
+        assert!(!suggest.includes_conversion());
+        assert!(suggest.suggest("t6th").is_empty());
+
+        suggest.set_include_conversion(true);
+        assert_eq!(suggest.suggest("t6th"), ["ত৬থ"]);
+        assert!(suggest.suggest("").is_empty());
+
+        // The conversion isn't repeated among the dictionary words
+        let words = suggest.suggest("ami");
+        assert_eq!(words[0], "আমি");
+        assert_eq!(words.iter().filter(|w| *w == "আমি").count(), 1);
+        assert_eq!(suggest.suggest_top_k("ami", 2), words[..2]);
+        assert_eq!(suggest.suggest_top_k("t6th", 2), ["ত৬থ"]);
+
+        let detailed = suggest.suggest_detailed("sari");
+        assert_eq!(detailed[0].word, "সারি");
+        assert_eq!(detailed[0].source, Source::Conversion);
+        assert_eq!(detailed.iter().filter(|s| s.word == "সারি").count(), 1);
+    }
+
+    #[test]
+    fn test_autocorrect() {
+        let mut suggest = Suggest::new();
+
+        let words = suggest.suggest("computer");
+        assert_eq!(words[0], "কম্পিউটার");
+        assert_eq!(words.iter().filter(|w| *w == "কম্পিউটার").count(), 1);
+        assert_eq!(suggest.suggest("banglades")[0], "বাংলাদেশ");
+        assert_eq!(suggest.suggest_top_k("(dhaka)", 1), ["(ঢাকা)"]);
+
+        let detailed = suggest.suggest_detailed("Dhaka");
+        assert_eq!(detailed[0].word, "ঢাকা");
+        assert_eq!(detailed[0].source, Source::Autocorrect);
+        assert_eq!(detailed.iter().filter(|s| s.word == "ঢাকা").count(), 1);
+
+        // Before the phonetic conversion
+        suggest.set_include_conversion(true);
+        let detailed = suggest.suggest_detailed("doctor");
+        assert_eq!(detailed[0].word, "ডাক্তার");
+        assert_eq!(detailed[0].source, Source::Autocorrect);
+        assert_eq!(detailed[1].source, Source::Conversion);
+
+        // The words of the longer inputs come after the words matching the
+        // input, before their completions
+        let mut suggest = Suggest::builder()
+            .autocorrect(r#"{ "korim": "করিম", "koro": "করো" }"#)
+            .build()
+            .unwrap();
+        let words = suggest.suggest("kor");
+        assert_eq!(words[0], "কর");
+        assert_eq!(words[words.len() - 2..], ["করো", "করিম"]);
+
+        suggest.set_completion_limit(2);
+        let completed = suggest.suggest("kor");
+        assert_eq!(completed[..words.len()], words);
+        assert_eq!(completed.len(), words.len() + 2);
+        assert_eq!(
+            suggest.suggest_top_k("kor", words.len() - 1),
+            words[..words.len() - 1]
+        );
+
+        let detailed = suggest.suggest_detailed("kor");
+        assert_eq!(detailed[words.len() - 2].word, "করো");
+        assert_eq!(detailed[words.len() - 2].source, Source::Autocorrect);
+        assert_eq!(detailed[words.len()].source, Source::Completion);
+    }
+
+    #[test]
+    fn test_english() {
+        let mut suggest = Suggest::new();
+        assert!(!suggest.includes_english());
+        assert!(!suggest
+            .suggest("computer")
+            .contains(&"computer".to_string()));
+
+        suggest.set_include_english(true);
+        let words = suggest.suggest("computer");
+        assert_eq!(words[..2], ["কম্পিউটার", "computer"]);
+        assert_eq!(suggest.suggest_top_k("computer", 2), words[..2]);
+        assert_eq!(suggest.suggest_top_k("computer", 1), words[..1]);
+
+        // As it is typed
+        assert_eq!(suggest.suggest("Computer,")[1], "Computer,");
+        assert!(!suggest
+            .suggest("kompiutar")
+            .contains(&"kompiutar".to_string()));
+
+        let detailed = suggest.suggest_detailed("email");
+        assert_eq!(detailed[1].word, "email");
+        assert_eq!(detailed[1].source, Source::English);
+        assert_eq!(detailed[1].score, detailed[0].score);
+
+        let suggest = {
+            let mut suggest = Suggest::builder()
+                .english_words_fst(FstTree::from_words([("zzz", 0)]).as_bytes())
+                .build()
+                .unwrap();
+            suggest.set_include_english(true);
+            suggest
+        };
+        assert_eq!(suggest.suggest("zzz")[1], "zzz");
+        assert!(suggest.suggest("computer").iter().all(|w| w != "computer"));
+    }
+
+    #[test]
+    fn test_completions() {
+        let mut suggest = Suggest::new();
+        assert_eq!(suggest.completion_limit(), 0);
+        let matching = suggest.suggest("bistar");
+        assert!(!matching.contains(&"বিস্তারিত".to_string()));
+
+        suggest.set_completion_limit(5);
+        let words = suggest.suggest("bistar");
+        assert_eq!(words.len(), matching.len() + 5);
+        assert_eq!(words[..matching.len()], matching);
+        // The shortest completions first
+        assert_eq!(words[matching.len()], "বিস্তারিত");
+        assert_eq!(suggest.suggest_top_k("bistar", words.len()), words);
+        assert_eq!(suggest.suggest_top_k("bistar", 2), words[..2]);
+
+        // A selected completion comes first, even if enough words match
+        let mut selected = Suggest::new();
+        selected.set_completion_limit(5);
+        selected.record_selection("bistar", "বিস্তারিত");
+        let ranked = selected.suggest("bistar");
+        assert_eq!(ranked[0], "বিস্তারিত");
+        assert_eq!(selected.suggest_top_k("bistar", 2), ranked[..2]);
+
+        let detailed = suggest.suggest_detailed("bistar");
+        assert_eq!(detailed.len(), words.len());
+        let completion = detailed.iter().find(|s| s.word == "বিস্তারিত").unwrap();
+        assert_eq!(completion.source, Source::Completion);
+        assert_eq!(completion.keys, ["b", "i", "s", "t", "a", "r"]);
+        assert!(detailed[matching.len()..]
+            .iter()
+            .all(|s| s.source == Source::Completion));
+
+        // From the user dictionary too
+        suggest
+            .user_dictionary_mut()
+            .add_with_weight("বিস্তারিতভাবেই", MAX_WEIGHT);
+        assert_eq!(suggest.suggest("bistar")[matching.len()], "বিস্তারিতভাবেই");
+        assert_eq!(suggest.session().suggestions(), Vec::<String>::new());
+
+        let mut session = suggest.session();
+        "bistar".chars().for_each(|c| session.push_char(c));
+        assert_eq!(session.suggestions(), suggest.suggest("bistar"));
+    }
+
+    #[test]
+    fn test_case_sensitive() {
+        let mut suggest = Suggest::new();
+        assert!(!suggest.is_case_sensitive());
+        assert_eq!(suggest.suggest("Tk"), suggest.suggest("tk"));
+
+        suggest.set_case_sensitive(true);
+        assert_eq!(suggest.suggest("Tk"), ["টক"]);
+        assert_eq!(suggest.suggest_top_k("Tk", 3), ["টক"]);
+        assert!(suggest.suggest("tk").contains(&"তক".to_string()));
+        assert!(suggest.suggest("bish").contains(&"বিশ".to_string()));
+        assert!(suggest
+            .suggest("biSh")
+            .iter()
+            .all(|word| word.contains('ষ')));
+        assert_eq!(suggest.suggest("SeSh"), ["শেষ"]);
+        let words = suggest.suggest("paRa");
+        assert!(!words.is_empty());
+        assert!(words.iter().all(|word| word.contains('\u{09DC}')));
+
+        let detailed = suggest.suggest_detailed("koNa");
+        assert!(!detailed.is_empty());
+        assert!(detailed.iter().all(|s| s.word.contains('ণ')));
+
+        // The other letters don't matter
+        assert_eq!(suggest.suggest("KoTha"), suggest.suggest("koTha"));
+    }
+
+    #[test]
+    fn test_punctuation() {
+        let mut suggest = Suggest::new();
+        let words = suggest.suggest("ami");
+
+        let punctuated: Vec<_> = words.iter().map(|w| format!("({w}),")).collect();
+        assert_eq!(suggest.suggest("(ami),"), punctuated);
+        let punctuated: Vec<_> = words.iter().map(|w| format!("{w}।")).collect();
+        assert_eq!(suggest.suggest("ami."), punctuated);
+        assert_eq!(suggest.suggest_top_k(" ami. ", 2), punctuated[..2]);
+        assert_eq!(suggest.suggest_detailed("ami.")[0].word, punctuated[0]);
+
+        // Only the punctuation
+        assert_eq!(suggest.suggest("."), ["।"]);
+        assert_eq!(suggest.suggest_top_k("$", 3), ["৳"]);
+        assert_eq!(suggest.suggest_detailed(".")[0].source, Source::Conversion);
+        assert!(suggest.suggest("").is_empty());
+
+        suggest.set_include_conversion(true);
+        assert_eq!(suggest.suggest("\"t6th\"")[0], "\"ত৬থ\"");
+
+        // The selections are shared with the input without punctuation
+        let other = words.iter().find(|w| *w != "আমি").unwrap();
+        suggest.record_selection("ami.", &format!("{other}।"));
+        assert_eq!(suggest.suggest("ami")[..2], ["আমি", other]);
+        assert_eq!(suggest.suggest("ami,")[1], format!("{other},"));
+    }
+
+    #[test]
+    fn test_reverse() {
+        let suggest = Suggest::new();
+
+        // The canonical inputs are spelled like for the phonetic rules
+        for (word, input) in [
+            ("আমি", "ami"),
+            ("বাংলা", "bangla"),
+            ("বাংলাদেশ", "bangladesh"),
+            ("উচ্চারণ", "uccaron"),
+            ("জ্ঞান", "ggan"),
+            ("প্রতিষ্ঠান", "protishthan"),
+            ("সংস্কৃতি", "songskrriti"),
+            ("কর", "kor"),
+            ("খবর", "khobor"),
+        ] {
+            assert_eq!(suggest.reverse(word)[0], input);
+        }
+        // The digits don't spell out the numbers
+        let inputs = suggest.reverse("চার");
+        assert_eq!(inputs[0], "car");
+        assert!(inputs.iter().all(|input| !input.contains('4')));
+        assert!(suggest.reverse("").is_empty());
+        assert!(suggest.reverse("abc").is_empty());
+
+        // Every input suggests the word back
+        for word in [
+            "আমি",
+            "সারি",
+            "স্বাধীনতা",
+            "দুঃখ",
+            "অংশচ্ছেদ",
+            "কম্পিউটার",
+            "র\u{200D}্যাব",
+        ] {
+            let inputs = suggest.reverse(word);
+            assert!(!inputs.is_empty(), "No input for {word}");
+            assert!(inputs.len() <= REVERSE_LIMIT);
+
+            for input in inputs {
+                assert!(
+                    suggest.suggest(&input).contains(&word.to_string()),
+                    "{input} doesn't suggest {word}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_joiners() {
+        let suggest = Suggest::new();
+
+        assert!(suggest.suggest("ryab").contains(&"র\u{200D}্যাব".to_string()));
+        assert!(suggest
+            .suggest("r``yab")
+            .contains(&"র\u{200D}্যাব".to_string()));
+        assert!(suggest
+            .suggest("r``yangs")
+            .contains(&"র\u{200C}্যাংগস".to_string()));
+        assert!(suggest
+            .suggest("allah")
+            .contains(&"আল্লাহ্\u{200C}".to_string()));
+        assert_eq!(sort(suggest.suggest("k`h")), ["কহ"]);
+    }
+
     #[test]
     fn test_empty_suggestion() {
         let suggest = Suggest::new();
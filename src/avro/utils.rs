@@ -1,3 +1,22 @@
+use std::ops::Range;
+
+/// Returns the byte range of the word in the Avro `input`, between the
+/// punctuation before and after it.
+///
+/// The word starts and ends with an ASCII letter, digit or backtick. If there
+/// is none, the range is empty and at the end of the input.
+pub fn word_range(input: &str) -> Range<usize> {
+    let is_word = |c: char| c.is_ascii_alphanumeric() || c == '`';
+
+    match input.find(is_word) {
+        Some(start) => {
+            let last = input.rfind(is_word).unwrap();
+            start..last + 1
+        }
+        None => input.len()..input.len(),
+    }
+}
+
 pub fn fix_string(s: &str) -> String {
     let s = s.trim();
     let mut prev = ' ';
@@ -17,6 +36,26 @@ pub fn fix_string(s: &str) -> String {
     result
 }
 
+/// Returns the number of characters to insert, remove or replace to turn `a`
+/// into `b`.
+pub fn distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, x) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+
+        for (j, &y) in b.iter().enumerate() {
+            let replaced = diagonal + usize::from(x != y);
+            diagonal = row[j + 1];
+            row[j + 1] = replaced.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+
+    row[b.len()]
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -29,4 +68,23 @@ mod tests {
         assert_eq!(fix_string("osomapto"), "Osomapto");
         assert_eq!(fix_string("6t``"), "6t``");
     }
+
+    #[test]
+    fn test_distance() {
+        assert_eq!(distance("ami", "ami"), 0);
+        assert_eq!(distance("ami", "amii"), 1);
+        assert_eq!(distance("উচ্চারন", "উচ্চারণ"), 1);
+        assert_eq!(distance("", "ami"), 3);
+        assert_eq!(distance("kitten", "sitting"), 3);
+    }
+
+    #[test]
+    fn test_word_range() {
+        assert_eq!(word_range("ami"), 0..3);
+        assert_eq!(word_range("(ami),"), 1..4);
+        assert_eq!(word_range("\"k`h.\""), 1..4);
+        assert_eq!(word_range("ami-tumi?"), 0..8);
+        assert_eq!(word_range(""), 0..0);
+        assert_eq!(word_range("..."), 3..3);
+    }
 }
@@ -1,28 +1,143 @@
-use std::collections::HashSet;
+use std::cmp::Reverse;
 
+#[cfg(feature = "embedded")]
 use once_cell::sync::Lazy;
 
-use crate::WORDS;
+pub use crate::normalization::normalize;
 
-const CHARS: [char; 61] = [
+use crate::{
+    dictionary::Dictionary,
+    encoding::{ZWJ, ZWNJ},
+    fst::{best_words, ranked_words, FstTree},
+    user_dictionary::UserDictionary,
+};
+
+const CHARS: [char; 63] = [
     'অ', 'আ', 'ই', 'ঈ', 'উ', 'ঊ', 'ঋ', 'এ', 'ঐ', 'ও', 'ঔ', 'া', 'ি', 'ী', 'ু', 'ূ', 'ৃ', 'ে', 'ৈ', 'ো',
     'ৌ', 'ক', 'খ', 'গ', 'ঘ', 'ঙ', 'চ', 'ছ', 'জ', 'ঝ', 'ঞ', 'ট', 'ঠ', 'ড', 'ঢ', 'ণ', 'ত', 'থ', 'দ',
     'ধ', 'ন', 'প', 'ফ', 'ব', 'ভ', 'ম', 'য', 'র', 'ল', 'শ', 'ষ', 'স', 'হ', 'ৎ', 'ড়', 'ঢ়', 'য়', 'ং',
-    'ঃ', 'ঁ', '্',
+    'ঃ', 'ঁ', '্', ZWNJ, ZWJ,
 ];
 
+/// A suggester of the embedded words, for the functions of the module.
+#[cfg(feature = "embedded")]
+static SUGGESTER: Lazy<Suggester> = Lazy::new(|| Suggester::new(Dictionary::embedded()));
+
+/// Returns the dictionary words completing the Bangla `word`, ordered by
+/// descending word weight. The embedded words have no weights, so they are
+/// ordered lexicographically.
+///
+/// The `word` is [normalized](normalize) first, so the suggestions start with
+/// its normalized form.
+#[cfg(feature = "embedded")]
 pub fn suggest(word: &str) -> Vec<String> {
+    SUGGESTER.suggest(word)
+}
+
+/// Returns the words of both the built-in dictionary and the user
+/// `dictionary` completing the Bangla `word`, ordered by descending word
+/// weight.
+#[cfg(feature = "embedded")]
+pub fn suggest_with_user_dictionary(word: &str, dictionary: &UserDictionary) -> Vec<String> {
+    SUGGESTER.suggest_with_user_dictionary(word, dictionary)
+}
+
+/// Returns the `k` best dictionary words completing the Bangla `word`.
+///
+/// The result is the same as the first `k` words of [`suggest`]. For the
+/// words of four characters or more, the best words are searched for and the
+/// search stops as soon as the `k` best ones are found. The shorter words are
+/// only completed a character ahead, so all their completions are collected
+/// and sorted instead, which is faster.
+#[cfg(feature = "embedded")]
+pub fn suggest_top_k(word: &str, k: usize) -> Vec<String> {
+    SUGGESTER.suggest_top_k(word, k)
+}
+
+/// Returns the dictionary words within `max_edits` character edits of the
+/// Bangla `word`, for correcting typos like `ি` instead of `ী`.
+///
+/// An edit is inserting, deleting or replacing a single character. The words
+/// are ordered by ascending edit distance and then like [`suggest`], so the
+/// `word` itself comes first if it is a dictionary word.
+#[cfg(feature = "embedded")]
+pub fn suggest_fuzzy(word: &str, max_edits: usize) -> Vec<String> {
+    SUGGESTER.suggest_fuzzy(word, max_edits)
+}
+
+/// Suggests the words of a [`Dictionary`] completing Bangla words, like the
+/// functions of the module do with the embedded words.
+#[derive(Clone)]
+pub struct Suggester {
+    dictionary: Dictionary,
+}
+
+impl Suggester {
+    /// Creates a suggester of the words of the `dictionary`.
+    pub fn new(dictionary: Dictionary) -> Self {
+        Suggester { dictionary }
+    }
+
+    /// Returns the dictionary words completing the Bangla `word`, ordered by
+    /// descending word weight. See [`suggest`].
+    pub fn suggest(&self, word: &str) -> Vec<String> {
+        ranked_words(completions(self.dictionary.words(), word))
+    }
+
+    /// Returns the words of both the dictionary and the user `dictionary`
+    /// completing the Bangla `word`, ordered by descending word weight.
+    pub fn suggest_with_user_dictionary(
+        &self,
+        word: &str,
+        dictionary: &UserDictionary,
+    ) -> Vec<String> {
+        let builtin = completions(self.dictionary.words(), word);
+        let user = completions(dictionary.tree(), word);
+
+        ranked_words(builtin.into_iter().chain(user))
+    }
+
+    /// Returns the `k` best dictionary words completing the Bangla `word`.
+    /// See [`suggest_top_k`].
+    pub fn suggest_top_k(&self, word: &str, k: usize) -> Vec<String> {
+        best_completions(self.dictionary.words(), word, k)
+            .into_iter()
+            .map(|(word, _)| word)
+            .collect()
+    }
+
+    /// Returns the dictionary words within `max_edits` character edits of
+    /// the Bangla `word`. See [`suggest_fuzzy`].
+    pub fn suggest_fuzzy(&self, word: &str, max_edits: usize) -> Vec<String> {
+        let word = normalize(word);
+
+        if word.is_empty() {
+            return Vec::new();
+        }
+
+        let mut words = self
+            .dictionary
+            .words()
+            .fuzzy_words(&word, max_edits as f64, |_, _| 1.0);
+        words.sort_by(|(a, da, wa), (b, db, wb)| {
+            da.total_cmp(db)
+                .then_with(|| wb.cmp(wa))
+                .then_with(|| a.cmp(b))
+        });
+
+        words.into_iter().map(|(word, _, _)| word).collect()
+    }
+}
+
+/// Collects the weighted words of the `words` FST completing the `word`.
+fn completions<D: AsRef<[u8]>>(words: &FstTree<D>, word: &str) -> Vec<(String, u64)> {
+    let word = &normalize(word);
+
     if word.is_empty() {
         return Vec::new();
     }
-    
-    let words = Lazy::force(&WORDS);
 
-    let need_chars_upto = match word.chars().count() {
-        1 => 0,
-        2..=3 => 1,
-        _ => 5,
-    };
+    let need_chars_upto = need_chars_upto(word);
 
     let node = if let Some(n) = words.matching_node(word) {
         n
@@ -35,7 +150,7 @@ pub fn suggest(word: &str) -> Vec<String> {
         .filter_map(|&c| node.get_matching_node_by_char(c))
         .collect();
 
-    for _ in 0..(need_chars_upto - 1) {
+    for _ in 1..need_chars_upto {
         let new_nodes: Vec<_> = nodes
             .iter()
             .flat_map(|node| {
@@ -48,17 +163,66 @@ pub fn suggest(word: &str) -> Vec<String> {
         nodes.extend(new_nodes);
     }
 
-    let suggestions = nodes
-        .into_iter()
-        .filter_map(|n| n.get_word())
-        .collect::<HashSet<_>>()
+    nodes
         .into_iter()
-        .collect::<Vec<_>>();
+        .filter_map(|n| n.get_weighted_word())
+        .collect()
+}
+
+/// Finds the `k` best weighted words of the `words` FST completing the `word`.
+fn best_completions<D: AsRef<[u8]>>(
+    words: &FstTree<D>,
+    word: &str,
+    k: usize,
+) -> Vec<(String, u64)> {
+    let word = &normalize(word);
+
+    if word.is_empty() {
+        return Vec::new();
+    }
 
-    suggestions
+    let max_chars = need_chars_upto(word).max(1);
+
+    // A character ahead, all the words are collected faster than the best
+    // ones are searched for
+    if max_chars == 1 {
+        let mut words = completions(words, word);
+        words.sort_by(|(a, wa), (b, wb)| (Reverse(wa), a).cmp(&(Reverse(wb), b)));
+        words.truncate(k);
+        return words;
+    }
+
+    let node = if let Some(n) = words.matching_node(word) {
+        n
+    } else {
+        return Vec::new();
+    };
+
+    best_words([(node, 0)], k, |node, &chars, next| {
+        if chars < max_chars {
+            next.extend(
+                CHARS
+                    .iter()
+                    .filter_map(|&c| node.get_matching_node_by_char(c))
+                    .map(|n| (n, chars + 1)),
+            );
+        }
+
+        // The word itself is not a suggestion
+        chars > 0
+    })
+}
+
+/// How many characters to look ahead for completing the `word`.
+fn need_chars_upto(word: &str) -> usize {
+    match word.chars().count() {
+        1 => 0,
+        2..=3 => 1,
+        _ => 5,
+    }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "embedded"))]
 mod tests {
     use super::*;
 
@@ -90,4 +254,82 @@ mod tests {
         assert_eq!(sort(sort(suggest("খ(১"))), Vec::<String>::new());
         assert_eq!(sort(sort(suggest("1"))), Vec::<String>::new());
     }
+
+    #[test]
+    fn test_normalized_input() {
+        // With the decomposed `ড়`
+        assert_eq!(
+            sort(suggest("\u{09AA}\u{09BE}\u{09A1}\u{09BC}")),
+            sort(suggest("\u{09AA}\u{09BE}\u{09DC}"))
+        );
+        assert!(!suggest("\u{09AA}\u{09BE}\u{09A1}\u{09BC}").is_empty());
+        // With the decomposed `ো`
+        assert_eq!(
+            suggest_top_k("\u{0986}\u{09B2}\u{09C7}\u{09BE}\u{099A}", 3),
+            suggest_top_k("\u{0986}\u{09B2}\u{09CB}\u{099A}", 3)
+        );
+        assert!(!suggest("\u{0986}\u{09B2}\u{09C7}\u{09BE}\u{099A}").is_empty());
+    }
+
+    #[test]
+    fn test_fuzzy_suggestions() {
+        assert_eq!(suggest_fuzzy("", 1), Vec::<String>::new());
+        assert_eq!(suggest_fuzzy("আমার", 0), ["আমার"]);
+
+        // `দিন` with a wrong kar
+        let words = suggest_fuzzy("দীন", 1);
+        assert_eq!(words[0], "দীন");
+        assert!(words.contains(&"দিন".to_string()));
+        // A misspelled `পূর্ব`
+        assert!(suggest_fuzzy("পুর্ব", 1).contains(&"পূর্ব".to_string()));
+        assert!(suggest_fuzzy("পুর্ব", 0).is_empty());
+
+        let words = suggest_fuzzy("কম্পিউটর", 2);
+        assert_eq!(words[0], "কম্পিউটার");
+        assert!(words.iter().all(|word| word.chars().count() >= 6));
+    }
+
+    #[test]
+    fn test_joiners() {
+        assert!(suggest("আল্লাহ").contains(&"আল্লাহ্\u{200C}".to_string()));
+        assert_eq!(
+            sort(suggest("র\u{200D}্যাং")),
+            ["র\u{200D}্যাংক", "র\u{200D}্যাংকিং"]
+        );
+    }
+
+    #[test]
+    fn test_top_k_suggestions() {
+        for word in ["", "আমা", "ই", "কম্পি", "আইনস্", "খ(১", "1", "ক", "বাংলা"]
+        {
+            let all = suggest(word);
+
+            for k in [0, 1, 3, 9, all.len() + 1] {
+                assert_eq!(suggest_top_k(word, k), all[..k.min(all.len())]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_user_dictionary() {
+        let mut dictionary = UserDictionary::new();
+        dictionary.add("আমাজন");
+        dictionary.add("আমাশ");
+        dictionary.add("আমার");
+
+        assert_eq!(
+            sort(suggest_with_user_dictionary("আমা", &dictionary)),
+            sort(vec![
+                "আমাশ".to_string(),
+                "আমান".to_string(),
+                "আমার".to_string(),
+                "আমা\u{9df}".to_string()
+            ])
+        );
+        assert_eq!(suggest_with_user_dictionary("আমাজ", &dictionary), ["আমাজন"]);
+        assert_eq!(
+            suggest_with_user_dictionary("", &dictionary),
+            Vec::<String>::new()
+        );
+    }
 }
@@ -0,0 +1,80 @@
+use std::{fs, path::Path, sync::Arc};
+
+use crate::{
+    fst::{FstData, FstTree},
+    Error,
+};
+
+/// The built-in words, for the Bangla suggestions and the spell checking.
+///
+/// Without the `embedded` feature, the words FST generated by the `generate`
+/// tool is loaded at runtime. The dictionary is cheap to clone, so the same
+/// words can be shared by a [`bangla::Suggester`](crate::bangla::Suggester)
+/// and a [`spell::SpellChecker`](crate::spell::SpellChecker).
+#[derive(Clone)]
+pub struct Dictionary {
+    words: Arc<FstTree<FstData>>,
+}
+
+impl Dictionary {
+    /// Returns the dictionary of the embedded words.
+    #[cfg(feature = "embedded")]
+    pub fn embedded() -> Self {
+        Self::from_data(FstData::Static(crate::WORDS_FST)).expect("The embedded words are valid")
+    }
+
+    /// Loads the dictionary from the bytes of a words FST.
+    pub fn from_fst(bytes: impl Into<Vec<u8>>) -> Result<Self, Error> {
+        Self::from_data(FstData::Owned(bytes.into()))
+    }
+
+    /// Loads the dictionary from the words FST in the file at `path`.
+    pub fn from_fst_file(path: impl AsRef<Path>) -> Result<Self, Error> {
+        Self::from_fst(fs::read(path)?)
+    }
+
+    /// Memory-maps the words FST in the file at `path`, instead of reading it
+    /// into the memory.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified or truncated while the dictionary is
+    /// alive. See [`memmap2::Mmap::map`] for details.
+    #[cfg(feature = "mmap")]
+    pub unsafe fn from_fst_mmap(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let file = fs::File::open(path)?;
+        Self::from_data(FstData::Mapped(memmap2::Mmap::map(&file)?))
+    }
+
+    fn from_data(data: FstData) -> Result<Self, Error> {
+        Ok(Dictionary {
+            words: Arc::new(FstTree::try_from_fst(data)?),
+        })
+    }
+
+    /// Returns the FST of the words.
+    pub(crate) fn words(&self) -> &FstTree<FstData> {
+        &self.words
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dictionary() {
+        let words = FstTree::from_words([("আমি", 0)]);
+        let dictionary = Dictionary::from_fst(words.as_bytes()).unwrap();
+        assert!(dictionary.words().matching_node("আমি").is_some());
+
+        assert!(matches!(
+            Dictionary::from_fst(vec![1, 2, 3]),
+            Err(Error::InvalidFst(_))
+        ));
+        assert!(matches!(
+            Dictionary::from_fst_file("missing.fst"),
+            Err(Error::Io(_))
+        ));
+    }
+}
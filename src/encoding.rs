@@ -0,0 +1,93 @@
+//! The single byte encoding of the characters in the FSTs.
+//!
+//! This file is also included by the `generate` tool, so it must not depend on
+//! anything else in the crate.
+//!
+//! | Characters                          | Bytes         |
+//! | ----------------------------------- | ------------- |
+//! | ASCII (`U+0000..=U+007F`)           | `0x00..=0x7F` |
+//! | Bengali block (`U+0980..=U+09FF`)   | `0x80..=0xFF` |
+//! | ZWNJ (`U+200C`)                     | `0x8D`        |
+//! | ZWJ (`U+200D`)                      | `0x8E`        |
+//!
+//! The joiners take the bytes of the unassigned code points `U+098D` and
+//! `U+098E`, which are therefore not supported. Every other character is
+//! rejected instead of being truncated into the byte of another one.
+
+/// The zero width non-joiner.
+pub const ZWNJ: char = '\u{200C}';
+
+/// The zero width joiner.
+pub const ZWJ: char = '\u{200D}';
+
+const BENGALI_START: u32 = 0x0980;
+const ZWNJ_BYTE: u8 = 0x8D;
+const ZWJ_BYTE: u8 = 0x8E;
+
+/// Encodes the character `c` into its byte, or `None` if it's not supported.
+pub fn encode(c: char) -> Option<u8> {
+    match c {
+        ZWNJ => Some(ZWNJ_BYTE),
+        ZWJ => Some(ZWJ_BYTE),
+        '\u{098D}' | '\u{098E}' => None,
+        '\u{0000}'..='\u{007F}' => Some(c as u8),
+        '\u{0980}'..='\u{09FF}' => Some((c as u32 - BENGALI_START) as u8 + 0x80),
+        _ => None,
+    }
+}
+
+/// Decodes the `byte` back into its character.
+pub fn decode(byte: u8) -> char {
+    match byte {
+        ZWNJ_BYTE => ZWNJ,
+        ZWJ_BYTE => ZWJ,
+        0x00..=0x7F => byte as char,
+        _ => char::from_u32(BENGALI_START + (byte - 0x80) as u32).unwrap(),
+    }
+}
+
+/// Encodes the `word`, or returns `None` if any of its characters is not supported.
+pub fn encode_str(word: &str) -> Option<Vec<u8>> {
+    word.chars().map(encode).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_collisions() {
+        let mut chars = [None; 256];
+
+        for c in (0..=0x10FFFF).filter_map(char::from_u32) {
+            if let Some(byte) = encode(c) {
+                if let Some(other) = chars[byte as usize].replace(c) {
+                    panic!("{c:?} and {other:?} are both encoded as {byte:#04x}");
+                }
+                assert_eq!(decode(byte), c);
+            }
+        }
+
+        // Every byte is used
+        assert!(chars.iter().all(Option::is_some));
+    }
+
+    #[test]
+    fn test_encoding() {
+        assert_eq!(encode('a'), Some(b'a'));
+        assert_eq!(encode('ক'), Some(0x95));
+        assert_eq!(encode(ZWNJ), Some(0x8D));
+        assert_eq!(encode(ZWJ), Some(0x8E));
+        assert_eq!(encode('\u{098D}'), None);
+        // Devanagari KA would be `0x95` if truncated
+        assert_eq!(encode('क'), None);
+        assert_eq!(encode('\u{0200}'), None);
+
+        assert_eq!(
+            encode_str("র\u{200C}্যাব"),
+            Some(vec![0xB0, 0x8D, 0xCD, 0xAF, 0xBE, 0xAC])
+        );
+        assert_eq!(encode_str("আমি"), Some(vec![0x86, 0xAE, 0xBF]));
+        assert_eq!(encode_str("আমিक"), None);
+    }
+}
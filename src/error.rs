@@ -0,0 +1,93 @@
+use std::{fmt, io};
+
+/// The errors which can occur while loading or saving the data of a suggester.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// The bytes are not a valid FST.
+    InvalidFst(fst::Error),
+    /// The pattern table or the common suffixes are not valid JSON of the
+    /// expected format.
+    InvalidPatterns(serde_json::Error),
+    /// The rules of the phonetic conversion are not valid JSON of the format
+    /// of `phonetic-rules.json`.
+    InvalidPhoneticRules(serde_json::Error),
+    /// The autocorrect words are not a valid JSON object of the words by their
+    /// inputs.
+    InvalidAutocorrect(serde_json::Error),
+    /// The saved selection history is not valid JSON of its format.
+    InvalidSelectionHistory(serde_json::Error),
+    /// The weight on this line of a saved user dictionary, counted from `1`,
+    /// is not a number.
+    InvalidUserDictionary { line: usize },
+    /// The pattern with this key has no transliteration block.
+    MissingPattern(String),
+    /// The pattern key can't be typed, as it isn't made of the lowercase
+    /// letters, the digits, `` ` `` and `O` of a normalized Avro input.
+    InvalidPatternKey(String),
+    /// The transliterations of the pattern can't be expanded from its regex,
+    /// for the given reason.
+    UnsupportedRegex {
+        key: String,
+        regex: String,
+        reason: String,
+    },
+    /// The data could not be read or written.
+    Io(io::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidFst(e) => write!(f, "Invalid FST: {e}"),
+            Error::InvalidPatterns(e) => write!(f, "Malformed patterns: {e}"),
+            Error::InvalidPhoneticRules(e) => write!(f, "Malformed phonetic rules: {e}"),
+            Error::InvalidAutocorrect(e) => write!(f, "Malformed autocorrect words: {e}"),
+            Error::InvalidSelectionHistory(e) => write!(f, "Malformed selection history: {e}"),
+            Error::InvalidUserDictionary { line } => {
+                write!(
+                    f,
+                    "Invalid word weight on line {line} of the user dictionary"
+                )
+            }
+            Error::MissingPattern(key) => write!(f, "Missing the block of the pattern {key:?}"),
+            Error::InvalidPatternKey(key) => write!(f, "Invalid pattern key {key:?}"),
+            Error::UnsupportedRegex { key, regex, reason } => {
+                write!(
+                    f,
+                    "Unsupported regex {regex:?} of the pattern {key:?}: {reason}"
+                )
+            }
+            Error::Io(e) => write!(f, "Failed to read or write the data: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::InvalidFst(e) => Some(e),
+            Error::InvalidPatterns(e)
+            | Error::InvalidPhoneticRules(e)
+            | Error::InvalidAutocorrect(e)
+            | Error::InvalidSelectionHistory(e) => Some(e),
+            Error::InvalidUserDictionary { .. }
+            | Error::MissingPattern(_)
+            | Error::InvalidPatternKey(_)
+            | Error::UnsupportedRegex { .. } => None,
+            Error::Io(e) => Some(e),
+        }
+    }
+}
+
+impl From<fst::Error> for Error {
+    fn from(e: fst::Error) -> Self {
+        Error::InvalidFst(e)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
@@ -1,4 +1,104 @@
-use fst::raw::{Fst, Node};
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::{BTreeMap, BinaryHeap, HashSet},
+};
+
+use fst::raw::{Fst, Node, Output};
+
+use crate::{encoding, Error};
+
+/// The highest weight a word can carry.
+///
+/// Words are stored in the FST with `MAX_WEIGHT - weight` as their output, so
+/// that the accumulated output of any node is the cost of the best word below it.
+pub const MAX_WEIGHT: u64 = u32::MAX as u64;
+
+/// The bytes of an FST, either embedded in the binary or loaded at runtime.
+pub enum FstData {
+    Static(&'static [u8]),
+    Owned(Vec<u8>),
+    #[cfg(feature = "mmap")]
+    Mapped(memmap2::Mmap),
+}
+
+impl AsRef<[u8]> for FstData {
+    fn as_ref(&self) -> &[u8] {
+        match self {
+            FstData::Static(data) => data,
+            FstData::Owned(data) => data,
+            #[cfg(feature = "mmap")]
+            FstData::Mapped(data) => data,
+        }
+    }
+}
+
+/// Texts to follow from an FST node all at once, like the transliterations of
+/// an Avro pattern.
+///
+/// The texts are kept in a trie of their encoding, so that the prefix shared
+/// by several texts is only walked once, and the texts starting with a prefix
+/// which isn't found are skipped together.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TextSet {
+    texts: Vec<String>,
+    /// The nodes of the trie, the root first.
+    nodes: Vec<TrieNode>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+struct TrieNode {
+    /// The next bytes and their nodes.
+    children: Vec<(u8, usize)>,
+    /// The indices of the texts ending at the node.
+    texts: Vec<usize>,
+}
+
+impl TextSet {
+    /// Creates the set of the `texts`. The texts with characters which can't
+    /// be encoded are left out, as they are never found.
+    pub fn new<'t, I>(texts: I) -> Self
+    where
+        I: IntoIterator<Item = &'t str>,
+    {
+        let mut set = TextSet {
+            texts: Vec::new(),
+            nodes: vec![TrieNode::default()],
+        };
+
+        for text in texts {
+            let index = set.texts.len();
+            set.texts.push(text.to_string());
+
+            let bytes = match encoding::encode_str(text) {
+                Some(bytes) => bytes,
+                None => continue,
+            };
+            let mut node = 0;
+
+            for byte in bytes {
+                let child = set.nodes[node]
+                    .children
+                    .iter()
+                    .find(|&&(b, _)| b == byte)
+                    .map(|&(_, child)| child);
+
+                node = match child {
+                    Some(child) => child,
+                    None => {
+                        set.nodes.push(TrieNode::default());
+                        let child = set.nodes.len() - 1;
+                        set.nodes[node].children.push((byte, child));
+                        child
+                    }
+                };
+            }
+
+            set.nodes[node].texts.push(index);
+        }
+
+        set
+    }
+}
 
 #[derive(Clone)]
 pub struct FstTree<D: AsRef<[u8]>> {
@@ -6,39 +106,41 @@ pub struct FstTree<D: AsRef<[u8]>> {
 }
 
 impl<D: AsRef<[u8]>> FstTree<D> {
-    pub fn from_fst(data: D) -> FstTree<D> {
-        let fst = Fst::new(data).expect("Failed to create FST from bytes");
-        Self { fst }
+    pub fn try_from_fst(data: D) -> Result<FstTree<D>, Error> {
+        let fst = Fst::new(data)?;
+        Ok(Self { fst })
     }
 
-    pub fn match_longest_common_prefix<'a>(&self, prefix: &'a str) -> (&'a str, &'a str, bool) {
-        let mut iter = prefix.chars();
-        let mut index = 0;
+    /// Returns the prefixes of the `word` which are in the FST, the shortest
+    /// first.
+    pub fn matching_prefixes<'a>(&self, word: &'a str) -> Vec<&'a str> {
+        let mut prefixes = Vec::new();
         let mut node = self.fst.root();
 
-        while let Some(c) = iter.next() {
-            match node.find_input(c as u8) {
-                Some(addr) => {
-                    node = self.fst.node(node.transition_addr(addr));
-                    index += c.len_utf8();
-                }
+        for (index, c) in word.char_indices() {
+            match encoding::encode(c).and_then(|byte| node.find_input(byte)) {
+                Some(addr) => node = self.fst.node(node.transition_addr(addr)),
                 None => break,
             }
-        }
 
-        let (matched_prefix, remaining) = prefix.split_at(index);
+            if node.is_final() {
+                prefixes.push(&word[..index + c.len_utf8()]);
+            }
+        }
 
-        (matched_prefix, remaining, node.is_final())
+        prefixes
     }
 
     pub fn matching_node<'a>(&'a self, word: &str) -> Option<FstNode<'a, D>> {
-        let mut iter = word.chars();
         let mut node = self.fst.root();
+        let mut output = Output::zero();
 
-        while let Some(c) = iter.next() {
-            match node.find_input(c as u8) {
+        for c in word.chars() {
+            match encoding::encode(c).and_then(|byte| node.find_input(byte)) {
                 Some(addr) => {
-                    node = self.fst.node(node.transition_addr(addr));
+                    let transition = node.transition(addr);
+                    output = output.cat(transition.out);
+                    node = self.fst.node(transition.addr);
                 }
                 None => return None,
             }
@@ -47,22 +149,97 @@ impl<D: AsRef<[u8]>> FstTree<D> {
         Some(FstNode {
             fst: &self.fst,
             node,
+            output,
             word: word.to_string(),
         })
     }
 }
 
-#[cfg(test)]
+impl<D: AsRef<[u8]>> FstTree<D> {
+    /// Finds the words within the edit distance `max_cost` of the `word`,
+    /// along with their distances and weights.
+    ///
+    /// The FST is walked along with a Levenshtein automaton over the
+    /// characters of the `word`, whose states are the rows of the edit
+    /// distance table. Branches are pruned as soon as every entry of the row
+    /// exceeds `max_cost`. Inserting or deleting a character costs `1`, and
+    /// replacing one character with another costs `substitution(from, to)`.
+    #[cfg_attr(not(feature = "embedded"), allow(dead_code))]
+    pub fn fuzzy_words<F>(
+        &self,
+        word: &str,
+        max_cost: f64,
+        substitution: F,
+    ) -> Vec<(String, f64, u64)>
+    where
+        F: Fn(char, char) -> f64,
+    {
+        let chars: Vec<char> = word.chars().collect();
+        let mut found = Vec::new();
+
+        let root = match self.matching_node("") {
+            Some(root) => root,
+            None => return found,
+        };
+        let first_row: Vec<f64> = (0..=chars.len()).map(|i| i as f64).collect();
+        let mut stack = vec![(root, first_row)];
+
+        while let Some((node, row)) = stack.pop() {
+            let distance = row[chars.len()];
+
+            if distance <= max_cost {
+                if let Some((word, weight)) = node.clone().get_weighted_word() {
+                    found.push((word, distance, weight));
+                }
+            }
+
+            for child in node.children() {
+                let c = child.word().chars().next_back().unwrap();
+                let mut next = Vec::with_capacity(row.len());
+                next.push(row[0] + 1.0);
+
+                for (i, &w) in chars.iter().enumerate() {
+                    let replace = if w == c { 0.0 } else { substitution(w, c) };
+                    let cost = (row[i] + replace).min(row[i + 1] + 1.0).min(next[i] + 1.0);
+                    next.push(cost);
+                }
+
+                if next.iter().any(|&cost| cost <= max_cost) {
+                    stack.push((child, next));
+                }
+            }
+        }
+
+        found
+    }
+}
+
 impl FstTree<Vec<u8>> {
-    fn from_strings(mut set: Vec<&str>) -> Self {
-        set.sort();
+    /// Builds an in-memory FST from the `words` and their weights.
+    ///
+    /// For duplicate words, the highest weight is kept. Words with characters
+    /// which can't be encoded are left out.
+    pub fn from_words<'w, I>(words: I) -> Self
+    where
+        I: IntoIterator<Item = (&'w str, u64)>,
+    {
+        let mut weights: BTreeMap<Vec<u8>, u64> = BTreeMap::new();
+
+        for (word, weight) in words {
+            let numbered_word = match encoding::encode_str(word) {
+                Some(numbered_word) => numbered_word,
+                None => continue,
+            };
+            let entry = weights.entry(numbered_word).or_default();
+            *entry = (*entry).max(weight.min(MAX_WEIGHT));
+        }
 
         let mut builder = fst::raw::Builder::memory();
 
-        for word in set {
-            // Convert each Bengali character to its single byte representation
-            let numbered_word = word.chars().map(|c| c as u8).collect::<Vec<u8>>();
-            builder.add(&numbered_word).unwrap();
+        for (numbered_word, weight) in weights {
+            builder
+                .insert(numbered_word, MAX_WEIGHT - weight)
+                .expect("Keys are sorted and unique");
         }
 
         Self {
@@ -71,22 +248,55 @@ impl FstTree<Vec<u8>> {
     }
 }
 
-#[derive(Clone)]
+#[cfg(test)]
+impl FstTree<Vec<u8>> {
+    fn from_strings(set: Vec<&str>) -> Self {
+        Self::from_words(set.into_iter().map(|word| (word, 0)))
+    }
+
+    fn from_weighted_strings(set: Vec<(&str, u64)>) -> Self {
+        Self::from_words(set)
+    }
+
+    /// The bytes of the FST.
+    #[cfg_attr(not(feature = "embedded"), allow(dead_code))]
+    pub(crate) fn as_bytes(&self) -> &[u8] {
+        self.fst.as_bytes()
+    }
+}
+
 pub struct FstNode<'a, D: AsRef<[u8]>> {
     fst: &'a Fst<D>,
     node: Node<'a>,
+    output: Output,
     word: String,
 }
 
+impl<D: AsRef<[u8]>> Clone for FstNode<'_, D> {
+    fn clone(&self) -> Self {
+        FstNode {
+            fst: self.fst,
+            node: self.node,
+            output: self.output,
+            word: self.word.clone(),
+        }
+    }
+}
+
 impl<'a, D: AsRef<[u8]>> FstNode<'a, D> {
+    /// Follows the `suffix` from this node. The texts to follow are given
+    /// all at once with [`FstNode::matching_nodes`] instead.
+    #[cfg(test)]
     pub fn get_matching_node(&self, suffix: &str) -> Option<FstNode<'a, D>> {
-        let mut iter = suffix.chars();
         let mut node = self.node;
+        let mut output = self.output;
 
-        while let Some(c) = iter.next() {
-            match node.find_input(c as u8) {
+        for c in suffix.chars() {
+            match encoding::encode(c).and_then(|byte| node.find_input(byte)) {
                 Some(addr) => {
-                    node = self.fst.node(node.transition_addr(addr));
+                    let transition = node.transition(addr);
+                    output = output.cat(transition.out);
+                    node = self.fst.node(transition.addr);
                 }
                 None => return None,
             }
@@ -97,27 +307,95 @@ impl<'a, D: AsRef<[u8]>> FstNode<'a, D> {
         Some(FstNode {
             fst: self.fst,
             node,
+            output,
             word,
         })
     }
 
-    pub fn get_matching_node_by_char(&self, suffix: char) -> Option<FstNode<'a, D>> {
-        let mut node = self.node;
+    /// Follows each of the `texts` from this node, returning the nodes found
+    /// along with the index of their text, in the order the texts were given.
+    pub fn matching_nodes(&self, texts: &TextSet) -> Vec<(usize, FstNode<'a, D>)> {
+        let mut found = Vec::new();
+
+        if !texts.nodes.is_empty() {
+            self.follow_trie(texts, 0, self.node, self.output, &mut found);
+            found.sort_by_key(|&(index, _)| index);
+        }
 
-        match node.find_input(suffix as u8) {
-            Some(addr) => {
-                node = self.fst.node(node.transition_addr(addr));
+        found
+    }
+
+    /// Follows the trie node `trie` of the `texts` from the FST `node`.
+    fn follow_trie(
+        &self,
+        texts: &TextSet,
+        trie: usize,
+        node: Node<'a>,
+        output: Output,
+        found: &mut Vec<(usize, FstNode<'a, D>)>,
+    ) {
+        let trie = &texts.nodes[trie];
+
+        for &index in &trie.texts {
+            found.push((
+                index,
+                FstNode {
+                    fst: self.fst,
+                    node,
+                    output,
+                    word: self.word.clone() + &texts.texts[index],
+                },
+            ));
+        }
+
+        for &(byte, child) in &trie.children {
+            if let Some(addr) = node.find_input(byte) {
+                let transition = node.transition(addr);
+                let next = self.fst.node(transition.addr);
+                self.follow_trie(texts, child, next, output.cat(transition.out), found);
             }
-            None => return None,
         }
+    }
+
+    #[cfg_attr(not(feature = "embedded"), allow(dead_code))]
+    pub fn get_matching_node_by_char(&self, suffix: char) -> Option<FstNode<'a, D>> {
+        let addr = self.node.find_input(encoding::encode(suffix)?)?;
+        let transition = self.node.transition(addr);
 
         Some(FstNode {
             fst: self.fst,
-            node,
+            node: self.fst.node(transition.addr),
+            output: self.output.cat(transition.out),
             word: format!("{}{}", self.word, suffix),
         })
     }
 
+    /// The nodes following this one, one for each next character.
+    #[cfg_attr(not(feature = "embedded"), allow(dead_code))]
+    pub fn children(&self) -> impl Iterator<Item = FstNode<'a, D>> + '_ {
+        self.node.transitions().map(move |transition| FstNode {
+            fst: self.fst,
+            node: self.fst.node(transition.addr),
+            output: self.output.cat(transition.out),
+            word: format!("{}{}", self.word, encoding::decode(transition.inp)),
+        })
+    }
+
+    /// Whether the word spelled by the path to this node is in the FST.
+    pub fn is_word(&self) -> bool {
+        self.node.is_final()
+    }
+
+    /// The word spelled by the path to this node.
+    pub fn word(&self) -> &str {
+        &self.word
+    }
+
+    /// The weight of the best word below this node.
+    pub fn best_weight(&self) -> u64 {
+        MAX_WEIGHT.saturating_sub(self.output.value())
+    }
+
     pub fn get_word(self) -> Option<String> {
         if self.node.is_final() {
             Some(self.word)
@@ -125,6 +403,160 @@ impl<'a, D: AsRef<[u8]>> FstNode<'a, D> {
             None
         }
     }
+
+    /// Returns the word along with its weight if this node completes a word.
+    pub fn get_weighted_word(self) -> Option<(String, u64)> {
+        let cost = self.output.cat(self.node.final_output()).value();
+        self.get_word()
+            .map(|word| (word, MAX_WEIGHT.saturating_sub(cost)))
+    }
+}
+
+/// Orders the weighted `words` by descending weight.
+///
+/// For duplicate words, the highest weight is kept. Words with the same
+/// weight are ordered lexicographically so that the result is deterministic.
+pub fn ranked_words<I>(words: I) -> Vec<String>
+where
+    I: IntoIterator<Item = (String, u64)>,
+{
+    // The highest weight of each word first, then the duplicates
+    let mut words: Vec<_> = words.into_iter().collect();
+    words.sort_unstable_by(|(a, wa), (b, wb)| (a, Reverse(wa)).cmp(&(b, Reverse(wb))));
+    words.dedup_by(|(a, _), (b, _)| a == b);
+
+    words.sort_by(|(a, wa), (b, wb)| (Reverse(wa), a).cmp(&(Reverse(wb), b)));
+
+    words.into_iter().map(|(word, _)| word).collect()
+}
+
+/// Finds the `k` best words with a best-first search over the words FST.
+///
+/// The search starts from the `start` nodes, each paired with a state of the
+/// caller's walk. `expand` pushes the nodes reachable from a node into the
+/// given vector and returns whether the node may complete a word. The words
+/// and their weights are returned in the same order as [`ranked_words`], but
+/// the search stops as soon as the `k` best words are certain.
+pub fn best_words<'a, D, S, I, F>(start: I, k: usize, expand: F) -> Vec<(String, u64)>
+where
+    D: AsRef<[u8]> + 'a,
+    I: IntoIterator<Item = (FstNode<'a, D>, S)>,
+    F: FnMut(&FstNode<'a, D>, &S, &mut Vec<(FstNode<'a, D>, S)>) -> bool,
+{
+    search(start, k, false, expand)
+}
+
+/// Like [`best_words`], but the words of the same weight are ordered by their
+/// length, the shortest first, and only then lexicographically.
+pub fn best_words_shortest_first<'a, D, S, I, F>(
+    start: I,
+    k: usize,
+    expand: F,
+) -> Vec<(String, u64)>
+where
+    D: AsRef<[u8]> + 'a,
+    I: IntoIterator<Item = (FstNode<'a, D>, S)>,
+    F: FnMut(&FstNode<'a, D>, &S, &mut Vec<(FstNode<'a, D>, S)>) -> bool,
+{
+    search(start, k, true, expand)
+}
+
+fn search<'a, D, S, I, F>(
+    start: I,
+    k: usize,
+    shortest_first: bool,
+    mut expand: F,
+) -> Vec<(String, u64)>
+where
+    D: AsRef<[u8]> + 'a,
+    I: IntoIterator<Item = (FstNode<'a, D>, S)>,
+    F: FnMut(&FstNode<'a, D>, &S, &mut Vec<(FstNode<'a, D>, S)>) -> bool,
+{
+    // Without the ordering by length, all the entries have the same length
+    let length = |word: &str| if shortest_first { word.len() } else { 0 };
+
+    let mut heap: BinaryHeap<Entry<'a, D, S>> = start
+        .into_iter()
+        .map(|(node, state)| Entry::Node(length(node.word()), node, state))
+        .collect();
+    let mut seen = HashSet::new();
+    let mut words = Vec::new();
+    let mut next = Vec::new();
+
+    while words.len() < k {
+        match heap.pop() {
+            Some(Entry::Word(_, word, weight)) => {
+                if seen.insert(word.clone()) {
+                    words.push((word, weight));
+                }
+            }
+            Some(Entry::Node(_, node, state)) => {
+                let accept = expand(&node, &state, &mut next);
+                heap.extend(
+                    next.drain(..)
+                        .map(|(node, state)| Entry::Node(length(node.word()), node, state)),
+                );
+
+                if accept {
+                    if let Some((word, weight)) = node.get_weighted_word() {
+                        heap.push(Entry::Word(length(&word), word, weight));
+                    }
+                }
+            }
+            None => break,
+        }
+    }
+
+    words
+}
+
+/// An entry of the [`best_words`] search queue, with the length it is ordered
+/// by.
+///
+/// Entries are ordered by their weight, and then by their length, the shortest
+/// first. As the words below a node are longer than the node, the length of a
+/// node is the shortest a word below it can be. Nodes come before words of the
+/// same weight and length, as they may still lead to a lexicographically
+/// smaller word.
+enum Entry<'a, D: AsRef<[u8]>, S> {
+    Node(usize, FstNode<'a, D>, S),
+    Word(usize, String, u64),
+}
+
+impl<D: AsRef<[u8]>, S> Entry<'_, D, S> {
+    fn key(&self) -> (u64, Reverse<usize>, bool, Reverse<&str>) {
+        match self {
+            Entry::Node(length, node, _) => (
+                node.best_weight(),
+                Reverse(*length),
+                true,
+                Reverse(node.word()),
+            ),
+            Entry::Word(length, word, weight) => {
+                (*weight, Reverse(*length), false, Reverse(word.as_str()))
+            }
+        }
+    }
+}
+
+impl<D: AsRef<[u8]>, S> PartialEq for Entry<'_, D, S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl<D: AsRef<[u8]>, S> Eq for Entry<'_, D, S> {}
+
+impl<D: AsRef<[u8]>, S> PartialOrd for Entry<'_, D, S> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<D: AsRef<[u8]>, S> Ord for Entry<'_, D, S> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key().cmp(&other.key())
+    }
 }
 
 #[cfg(test)]
@@ -132,37 +564,41 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_match_longest_common_prefix() {
-        let fst = FstTree::from_strings(vec![
-            "ক",
-            "কখগ",
-            "কখগঘঙ",
-            "চ",
-            "চছজ",
-            "চছজঝঞ",
-            "১",
-            "a",
-            "bc",
-            "abcd",
-        ]);
+    fn test_matching_prefixes() {
+        let fst = FstTree::from_strings(vec!["ক", "কখগ", "কখগঘঙ", "চ", "a", "abcd"]);
+
+        assert_eq!(fst.matching_prefixes("কখগঘঙচ"), ["ক", "কখগ", "কখগঘঙ"]);
+        assert_eq!(fst.matching_prefixes("কখ"), ["ক"]);
+        assert_eq!(fst.matching_prefixes("abc"), ["a"]);
+        assert!(fst.matching_prefixes("খ").is_empty());
+        assert!(fst.matching_prefixes("").is_empty());
+    }
 
-        assert_eq!(fst.match_longest_common_prefix("ক"), ("ক", "", true));
-        assert_eq!(
-            fst.match_longest_common_prefix("ক1234"),
-            ("ক", "1234", true)
-        );
-        assert_eq!(fst.match_longest_common_prefix("1234"), ("", "1234", false));
+    #[test]
+    fn test_matching_nodes() {
+        let fst = FstTree::from_strings(vec!["ক", "কখ", "কখগ", "কগ", "খ"]);
+        let texts = TextSet::new(["গ", "খগ", "", "খ", "খঘ", "a", "খ"]);
+
+        let node = fst.matching_node("ক").unwrap();
+        let found: Vec<_> = node
+            .matching_nodes(&texts)
+            .into_iter()
+            .map(|(index, node)| (index, node.word().to_string()))
+            .collect();
         assert_eq!(
-            fst.match_longest_common_prefix("কখগঘঙচছজঝঞ"),
-            ("কখগঘঙ", "চছজঝঞ", true)
+            found,
+            [
+                (0, "কগ".to_string()),
+                (1, "কখগ".to_string()),
+                (2, "ক".to_string()),
+                (3, "কখ".to_string()),
+                (6, "কখ".to_string())
+            ]
         );
 
-        assert_eq!(fst.match_longest_common_prefix("a"), ("a", "", true));
-        assert_eq!(fst.match_longest_common_prefix("a123"), ("a", "123", true));
-        assert_eq!(
-            fst.match_longest_common_prefix("abcdefg"),
-            ("abcd", "efg", true)
-        );
+        let node = fst.matching_node("খ").unwrap();
+        assert_eq!(node.matching_nodes(&texts).len(), 1);
+        assert!(node.matching_nodes(&TextSet::default()).is_empty());
     }
 
     #[test]
@@ -194,4 +630,130 @@ mod tests {
         let n4 = trie.matching_node("কখগঘ").unwrap();
         assert_eq!(n4.get_word(), None);
     }
+
+    #[test]
+    fn test_weighted_words() {
+        let fst = FstTree::from_weighted_strings(vec![
+            ("ক", 10),
+            ("কখ", 300),
+            ("কগ", 300),
+            ("কখগ", 5),
+            ("চ", 7),
+        ]);
+
+        let n1 = fst.matching_node("ক").unwrap();
+        assert_eq!(n1.clone().get_weighted_word(), Some(("ক".to_string(), 10)));
+
+        let n2 = n1.get_matching_node_by_char('খ').unwrap();
+        assert_eq!(
+            n2.clone().get_weighted_word(),
+            Some(("কখ".to_string(), 300))
+        );
+
+        let n3 = n2.get_matching_node("গ").unwrap();
+        assert_eq!(n3.get_weighted_word(), Some(("কখগ".to_string(), 5)));
+
+        let words = ["ক", "কখ", "কখগ", "কগ", "চ", "কখ"]
+            .iter()
+            .filter_map(|w| fst.matching_node(w)?.get_weighted_word());
+        assert_eq!(ranked_words(words), ["কখ", "কগ", "ক", "চ", "কখগ"]);
+
+        assert_eq!(
+            ranked_words(vec![
+                ("ক".to_string(), 1),
+                ("খ".to_string(), 2),
+                ("ক".to_string(), 3)
+            ]),
+            ["ক", "খ"]
+        );
+    }
+
+    #[test]
+    fn test_fuzzy_words() {
+        let fst =
+            FstTree::from_weighted_strings(vec![("কখ", 1), ("কখগ", 2), ("কঘ", 3), ("খগঘ", 4)]);
+        let fuzzy = |word, max_cost| {
+            let mut words = fst.fuzzy_words(word, max_cost, |_, _| 1.0);
+            words.sort_by(|a, b| a.0.cmp(&b.0));
+            words
+        };
+
+        assert_eq!(fuzzy("কখ", 0.0), [("কখ".to_string(), 0.0, 1)]);
+        assert_eq!(
+            fuzzy("কখ", 1.0),
+            [
+                ("কখ".to_string(), 0.0, 1),
+                ("কখগ".to_string(), 1.0, 2),
+                ("কঘ".to_string(), 1.0, 3)
+            ]
+        );
+        assert_eq!(
+            fuzzy("খগ", 1.0),
+            [("কখগ".to_string(), 1.0, 2), ("খগঘ".to_string(), 1.0, 4)]
+        );
+        assert!(fuzzy("ঙ", 0.5).is_empty());
+
+        // Cheaper substitutions
+        let words = fst.fuzzy_words("কগ", 0.5, |from, to| {
+            if (from, to) == ('গ', 'ঘ') {
+                0.5
+            } else {
+                1.0
+            }
+        });
+        assert_eq!(words, [("কঘ".to_string(), 0.5, 3)]);
+    }
+
+    #[test]
+    fn test_best_words() {
+        let fst = FstTree::from_weighted_strings(vec![
+            ("ক", 10),
+            ("কখ", 300),
+            ("কগ", 300),
+            ("কখগ", 5),
+            ("কখঘ", 400),
+            ("চ", 7),
+        ]);
+
+        let all = ranked_words(
+            ["ক", "কখ", "কগ", "কখগ", "কখঘ", "চ"]
+                .iter()
+                .filter_map(|w| fst.matching_node(w)?.get_weighted_word()),
+        );
+        assert_eq!(all, ["কখঘ", "কখ", "কগ", "ক", "চ", "কখগ"]);
+
+        // Walks the whole FST from the root, one character at a time.
+        fn walk<'a>(
+            node: &FstNode<'a, Vec<u8>>,
+            _: &(),
+            next: &mut Vec<(FstNode<'a, Vec<u8>>, ())>,
+        ) -> bool {
+            next.extend(
+                ['ক', 'খ', 'গ', 'ঘ', 'চ']
+                    .iter()
+                    .filter_map(|&c| node.get_matching_node_by_char(c))
+                    .map(|n| (n, ())),
+            );
+            true
+        }
+
+        for k in 0..=all.len() + 1 {
+            let root = fst.matching_node("").unwrap();
+            let best = best_words([(root, ())], k, walk);
+            let best: Vec<_> = best.into_iter().map(|(word, _)| word).collect();
+            assert_eq!(best, all[..k.min(all.len())]);
+        }
+
+        // Then by the length
+        let root = fst.matching_node("").unwrap();
+        let best = best_words_shortest_first([(root, ())], all.len(), walk);
+        let best: Vec<_> = best.into_iter().map(|(word, _)| word).collect();
+        assert_eq!(best, all);
+
+        let fst = FstTree::from_strings(vec!["কখঘ", "কখ", "খ", "চ", "কখগঘ"]);
+        let root = fst.matching_node("").unwrap();
+        let best = best_words_shortest_first([(root, ())], 4, walk);
+        let best: Vec<_> = best.into_iter().map(|(word, _)| word).collect();
+        assert_eq!(best, ["খ", "চ", "কখ", "কখঘ"]);
+    }
 }
@@ -1,10 +1,27 @@
-use once_cell::sync::Lazy;
+/// The embedded FST containing the valid Bengali words for suggestions.
+#[cfg(feature = "embedded")]
+pub(crate) const WORDS_FST: &[u8] = include_bytes!("words.fst");
 
-use crate::fst::FstTree;
-
-/// The FST containing the valid Bengali words for suggestions.
-static WORDS: Lazy<FstTree<&[u8]>> = Lazy::new(|| FstTree::from_fst(include_bytes!("words.fst")));
+/// The embedded FST containing the English words suggested along with the
+/// Bengali ones.
+#[cfg(feature = "embedded")]
+pub(crate) const ENGLISH_FST: &[u8] = include_bytes!("english.fst");
 
 pub mod avro;
 pub mod bangla;
+mod dictionary;
+// Only decoding is unused without the `embedded` feature, but the module can't
+// refer to the features as it's also included by the `generate` tool.
+#[cfg_attr(not(feature = "embedded"), allow(dead_code))]
+mod encoding;
+mod error;
 mod fst;
+mod normalization;
+pub mod spell;
+mod suggestion;
+mod user_dictionary;
+
+pub use dictionary::Dictionary;
+pub use error::Error;
+pub use suggestion::{Source, Suggestion};
+pub use user_dictionary::UserDictionary;
@@ -0,0 +1,96 @@
+//! The normalization of Bangla text into the form of the dictionary.
+//!
+//! This file is also included by the `generate` tool, so it must not depend on
+//! anything else in the crate.
+
+const NUKTA: char = '\u{09BC}';
+const HASANTA: char = '\u{09CD}';
+const E_KAR: char = '\u{09C7}';
+const AA_KAR: char = '\u{09BE}';
+const AU_LENGTH_MARK: char = '\u{09D7}';
+const ZWNJ: char = '\u{200C}';
+const ZWJ: char = '\u{200D}';
+
+/// Normalizes the Bangla `text` into the form used by the dictionary.
+///
+/// Different keyboards and input methods produce different code point
+/// sequences for the same letters. This converts them into a single form:
+///
+/// * `ড`, `ঢ` and `য` followed by the nukta (`়`) become the precomposed
+///   `\u{09DC}`, `\u{09DD}` and `\u{09DF}`.
+/// * `ে` followed by `া` or `ৗ` becomes the precomposed `ো` or `ৌ`.
+/// * `ত্` followed by a ZWJ or ZWNJ becomes the khanda-ta `ৎ`.
+///
+/// Note that this is not the same as the Unicode NFC, which decomposes the
+/// nukta letters.
+pub fn normalize(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        let composed = match (result.chars().next_back(), c) {
+            (Some('\u{09A1}'), NUKTA) => Some('\u{09DC}'),
+            (Some('\u{09A2}'), NUKTA) => Some('\u{09DD}'),
+            (Some('\u{09AF}'), NUKTA) => Some('\u{09DF}'),
+            (Some(E_KAR), AA_KAR) => Some('\u{09CB}'),
+            (Some(E_KAR), AU_LENGTH_MARK) => Some('\u{09CC}'),
+            (Some(HASANTA), ZWJ | ZWNJ) if result.ends_with("\u{09A4}\u{09CD}") => {
+                // The hasanta is replaced along with the ta
+                result.pop();
+                Some('\u{09CE}')
+            }
+            _ => None,
+        };
+
+        match composed {
+            Some(composed) => {
+                result.pop();
+                result.push(composed);
+            }
+            None => result.push(c),
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        // Nukta letters
+        assert_eq!(
+            normalize("\u{09AA}\u{09A1}\u{09BC}\u{09BE}"),
+            "\u{09AA}\u{09DC}\u{09BE}"
+        );
+        assert_eq!(normalize("\u{09A2}\u{09BC}"), "\u{09DD}");
+        assert_eq!(normalize("\u{0986}\u{09AF}\u{09BC}"), "\u{0986}\u{09DF}");
+        assert_eq!(normalize("\u{099C}\u{09BC}"), "\u{099C}\u{09BC}");
+        assert_eq!(normalize("\u{09BC}"), "\u{09BC}");
+
+        // Two-part vowel signs
+        assert_eq!(normalize("\u{0995}\u{09C7}\u{09BE}"), "\u{0995}\u{09CB}");
+        assert_eq!(normalize("\u{0995}\u{09C7}\u{09D7}"), "\u{0995}\u{09CC}");
+        assert_eq!(normalize("\u{0995}\u{09C7}"), "\u{0995}\u{09C7}");
+
+        // Khanda-ta
+        assert_eq!(
+            normalize("\u{0989}\u{09A4}\u{09CD}\u{200C}\u{09B8}\u{09AC}"),
+            "উৎসব"
+        );
+        assert_eq!(
+            normalize("\u{09AD}\u{09A4}\u{09CD}\u{200D}"),
+            "\u{09AD}\u{09CE}"
+        );
+        assert_eq!(
+            normalize("\u{09A6}\u{09CD}\u{200C}"),
+            "\u{09A6}\u{09CD}\u{200C}"
+        );
+
+        // Already normalized text is unchanged
+        for word in ["আমার", "\u{09AA}\u{09DC}\u{09BE}", "উৎসব", "ami", ""] {
+            assert_eq!(normalize(word), word);
+        }
+    }
+}
@@ -0,0 +1,232 @@
+use std::{collections::HashMap, ops::Range};
+
+#[cfg(feature = "embedded")]
+use once_cell::sync::Lazy;
+
+use crate::{
+    dictionary::Dictionary,
+    fst::MAX_WEIGHT,
+    normalization::normalize,
+    suggestion::{rank, Source, Suggestion},
+};
+
+mod tokenizer;
+
+/// The groups of characters which are commonly confused with each other.
+const CONFUSIONS: [&[char]; 7] = [
+    &['শ', 'ষ', 'স'],
+    &['ন', 'ণ'],
+    &['জ', 'য'],
+    &['ি', 'ী'],
+    &['ু', 'ূ'],
+    &['ই', 'ঈ'],
+    &['উ', 'ঊ'],
+];
+
+/// The cost of replacing a character with a commonly confused one.
+const CONFUSION_COST: f64 = 0.5;
+
+/// The highest total cost of the edits for a correction.
+///
+/// That is a single arbitrary edit along with a confusion, or up to three
+/// confusions.
+const MAX_COST: f64 = 1.5;
+
+/// The number of corrections offered for each misspelling by [`check_text`].
+const TEXT_CORRECTIONS: usize = 5;
+
+/// A word of a text which is not in the dictionary.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Misspelling {
+    /// The misspelled word, as it is in the text.
+    pub word: String,
+    /// The byte range of the word in the text.
+    pub span: Range<usize>,
+    /// The character range of the word in the text.
+    pub char_span: Range<usize>,
+    /// The likely corrections of the word, as returned by [`corrections`].
+    pub corrections: Vec<Suggestion>,
+}
+
+/// A spell checker of the embedded words, for the functions of the module.
+#[cfg(feature = "embedded")]
+static CHECKER: Lazy<SpellChecker> = Lazy::new(|| SpellChecker::new(Dictionary::embedded()));
+
+/// Finds the misspelled Bangla words of the running `text`.
+///
+/// The text is split into words at spaces, punctuation, the dari (`।`) and
+/// digits. Words with English letters are not checked. Each misspelling comes
+/// with up to five corrections, in the order they appear in the text.
+#[cfg(feature = "embedded")]
+pub fn check_text(text: &str) -> Vec<Misspelling> {
+    CHECKER.check_text(text)
+}
+
+/// Returns whether the Bangla `word` is in the dictionary.
+#[cfg(feature = "embedded")]
+pub fn is_correct(word: &str) -> bool {
+    CHECKER.is_correct(word)
+}
+
+/// Returns up to `limit` dictionary words which the Bangla `word` may be a
+/// misspelling of, the most likely first.
+///
+/// Inserting, deleting or replacing a character costs `1`, except replacing it
+/// with a commonly confused one like `ি` and `ী`, or `শ`, `ষ` and `স`, which
+/// costs less. The score of a correction is higher for cheaper edits, and then
+/// for higher word weights. The `word` itself is never a correction.
+#[cfg(feature = "embedded")]
+pub fn corrections(word: &str, limit: usize) -> Vec<Suggestion> {
+    CHECKER.corrections(word, limit)
+}
+
+/// Checks the spelling of Bangla words against a [`Dictionary`], like the
+/// functions of the module do with the embedded words.
+#[derive(Clone)]
+pub struct SpellChecker {
+    dictionary: Dictionary,
+}
+
+impl SpellChecker {
+    /// Creates a spell checker of the words of the `dictionary`.
+    pub fn new(dictionary: Dictionary) -> Self {
+        SpellChecker { dictionary }
+    }
+
+    /// Finds the misspelled Bangla words of the running `text`. See
+    /// [`check_text`].
+    pub fn check_text(&self, text: &str) -> Vec<Misspelling> {
+        // Misspellings tend to repeat in large texts
+        let mut cache: HashMap<&str, Vec<Suggestion>> = HashMap::new();
+
+        tokenizer::tokenize(text)
+            .into_iter()
+            .filter(|token| token.bangla && !self.is_correct(token.text))
+            .map(|token| Misspelling {
+                word: token.text.to_string(),
+                corrections: cache
+                    .entry(token.text)
+                    .or_insert_with(|| self.corrections(token.text, TEXT_CORRECTIONS))
+                    .clone(),
+                span: token.span,
+                char_span: token.char_span,
+            })
+            .collect()
+    }
+
+    /// Returns whether the Bangla `word` is in the dictionary.
+    pub fn is_correct(&self, word: &str) -> bool {
+        let word = normalize(word);
+
+        self.dictionary
+            .words()
+            .matching_node(&word)
+            .and_then(|node| node.get_word())
+            .is_some()
+    }
+
+    /// Returns up to `limit` dictionary words which the Bangla `word` may be
+    /// a misspelling of, the most likely first. See [`corrections`].
+    pub fn corrections(&self, word: &str, limit: usize) -> Vec<Suggestion> {
+        let word = normalize(word);
+
+        if word.is_empty() || limit == 0 {
+            return Vec::new();
+        }
+
+        let words = self
+            .dictionary
+            .words()
+            .fuzzy_words(&word, MAX_COST, substitution_cost);
+        let suggestions = words
+            .into_iter()
+            .filter(|(correction, _, _)| *correction != word)
+            .map(|(word, cost, weight)| Suggestion {
+                word,
+                // The cost always outweighs the word weight
+                score: weight as f64 - cost * (MAX_WEIGHT as f64 + 1.0),
+                source: Source::Correction,
+                keys: Vec::new(),
+                unmatched: Vec::new(),
+            })
+            .collect();
+
+        let mut suggestions = rank(suggestions);
+        suggestions.truncate(limit);
+        suggestions
+    }
+}
+
+/// The cost of replacing the character `from` with `to`.
+fn substitution_cost(from: char, to: char) -> f64 {
+    let confused = CONFUSIONS
+        .iter()
+        .any(|group| group.contains(&from) && group.contains(&to));
+
+    if confused {
+        CONFUSION_COST
+    } else {
+        1.0
+    }
+}
+
+#[cfg(all(test, feature = "embedded"))]
+mod tests {
+    use super::*;
+
+    fn words(suggestions: Vec<Suggestion>) -> Vec<String> {
+        suggestions.into_iter().map(|s| s.word).collect()
+    }
+
+    #[test]
+    fn test_is_correct() {
+        assert!(is_correct("আমার"));
+        assert!(is_correct("কম্পিউটার"));
+        // With the decomposed `ো`
+        assert!(is_correct(
+            "\u{0986}\u{09B2}\u{09C7}\u{09BE}\u{099A}\u{09A8}\u{09BE}"
+        ));
+        assert!(!is_correct("কম্পিউটর"));
+        assert!(!is_correct("কম্পি"));
+        assert!(!is_correct(""));
+    }
+
+    #[test]
+    fn test_corrections() {
+        assert!(corrections("", 5).is_empty());
+        assert!(corrections("আমার", 0).is_empty());
+
+        // Confusions come before the other edits
+        let suggestions = corrections("পুর্ব", 5);
+        assert_eq!(suggestions[0].word, "পূর্ব");
+        assert_eq!(suggestions[0].source, Source::Correction);
+        assert!(suggestions.len() <= 5);
+        assert!(suggestions
+            .windows(2)
+            .all(|pair| pair[0].score >= pair[1].score));
+
+        assert_eq!(corrections("সিক্ষা", 1)[0].word, "শিক্ষা");
+        assert_eq!(corrections("কম্পিউটর", 1)[0].word, "কম্পিউটার");
+        assert!(!words(corrections("আমার", 10)).contains(&"আমার".to_string()));
+    }
+
+    #[test]
+    fn test_check_text() {
+        assert!(check_text("").is_empty());
+        assert!(check_text("আমি বাংলা বই পড়ি।").is_empty());
+
+        let text = "আমার কম্পিউটর, Computer আর ১২টি কম্পিউটর।";
+        let misspellings = check_text(text);
+        assert_eq!(misspellings.len(), 2);
+
+        let first = &misspellings[0];
+        assert_eq!(first.word, "কম্পিউটর");
+        assert_eq!(&text[first.span.clone()], "কম্পিউটর");
+        assert_eq!(first.char_span, 5..13);
+        assert_eq!(first.corrections[0].word, "কম্পিউটার");
+        assert!(first.corrections.len() <= 5);
+
+        assert_eq!(misspellings[1].corrections, first.corrections);
+        assert!(misspellings[1].span.start > first.span.end);
+    }
+}
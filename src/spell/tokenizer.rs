@@ -0,0 +1,101 @@
+use std::ops::Range;
+
+use crate::encoding::{ZWJ, ZWNJ};
+
+/// A word of running text.
+#[derive(Debug, PartialEq)]
+pub(crate) struct Token<'t> {
+    pub text: &'t str,
+    /// The byte range of the word in the text.
+    pub span: Range<usize>,
+    /// The character range of the word in the text.
+    pub char_span: Range<usize>,
+    /// Whether the word is written only with Bangla letters.
+    pub bangla: bool,
+}
+
+/// Splits the `text` into words.
+///
+/// Words are the runs of Bangla letters, vowel signs and joiners, and of
+/// ASCII letters and digits. Everything else, like spaces, the dari (`।`),
+/// punctuation and Bangla digits, separates them.
+pub(crate) fn tokenize(text: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    // The byte and character offsets where the current word started
+    let mut start: Option<(usize, usize)> = None;
+    let mut bangla = true;
+
+    for (chars, (index, c)) in text.char_indices().enumerate() {
+        if is_bangla_letter(c) || c.is_ascii_alphanumeric() {
+            if start.is_none() {
+                start = Some((index, chars));
+                bangla = true;
+            }
+            bangla &= is_bangla_letter(c);
+        } else if let Some((start, char_start)) = start.take() {
+            tokens.push(Token {
+                text: &text[start..index],
+                span: start..index,
+                char_span: char_start..chars,
+                bangla,
+            });
+        }
+    }
+
+    if let Some((start, char_start)) = start {
+        tokens.push(Token {
+            text: &text[start..],
+            span: start..text.len(),
+            char_span: char_start..text.chars().count(),
+            bangla,
+        });
+    }
+
+    tokens
+}
+
+/// Whether the `c` can be a part of a Bangla word.
+fn is_bangla_letter(c: char) -> bool {
+    matches!(
+        c,
+        '\u{0980}'..='\u{09E3}' | '\u{09F0}' | '\u{09F1}' | '\u{09FE}' | ZWJ | ZWNJ
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words<'t>(tokens: &[Token<'t>]) -> Vec<&'t str> {
+        tokens.iter().map(|token| token.text).collect()
+    }
+
+    #[test]
+    fn test_tokenize() {
+        assert!(tokenize("").is_empty());
+        assert!(tokenize(" ।, ১২৩ ").is_empty());
+
+        let tokens = tokenize("আমি বাংলায় গান গাই।");
+        assert_eq!(words(&tokens), ["আমি", "বাংলায়", "গান", "গাই"]);
+        assert!(tokens.iter().all(|token| token.bangla));
+
+        let tokens = tokenize("(আমার) Rust-এর ১০টি বই, ok?");
+        assert_eq!(words(&tokens), ["আমার", "Rust", "এর", "টি", "বই", "ok"]);
+        assert_eq!(
+            tokens.iter().map(|t| t.bangla).collect::<Vec<_>>(),
+            [true, false, true, true, true, false]
+        );
+
+        let text = "ক খগ";
+        let tokens = tokenize(text);
+        assert_eq!(tokens[1].span, 4..10);
+        assert_eq!(&text[tokens[1].span.clone()], "খগ");
+        assert_eq!(tokens[1].char_span, 2..4);
+
+        // Joiners are a part of the words, and mixed words aren't Bangla
+        let tokens = tokenize("র\u{200D}্যাব abcক");
+        assert_eq!(words(&tokens), ["র\u{200D}্যাব", "abcক"]);
+        assert!(tokens[0].bangla);
+        assert!(!tokens[1].bangla);
+    }
+}
@@ -0,0 +1,76 @@
+use std::{cmp::Ordering, collections::HashMap, ops::Range};
+
+/// Where a [`Suggestion`] came from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Source {
+    /// Every segment of the input matched an Avro pattern.
+    Pattern,
+    /// One or more of the common suffixes (like `্য` or `্ব`) were inserted
+    /// after the pattern matches.
+    CommonSuffix,
+    /// The word is from the user dictionary.
+    UserDictionary,
+    /// The word is a spelling correction of the input.
+    Correction,
+    /// The word is the rule-based phonetic conversion of the input, which
+    /// may not be a dictionary word.
+    Conversion,
+    /// The word is the autocorrect word of the input, or of a longer input
+    /// starting with it.
+    Autocorrect,
+    /// The word is the input itself, as it is a known English word.
+    English,
+    /// The word extends a word matching the input, which is only a part of it.
+    Completion,
+}
+
+/// A suggested word along with the details of how it was produced.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Suggestion {
+    /// The suggested Bangla word.
+    pub word: String,
+    /// The ranking score of the word. Higher is better.
+    pub score: f64,
+    /// Where the suggestion came from.
+    pub source: Source,
+    /// The Avro pattern keys which produced each segment of the word.
+    pub keys: Vec<String>,
+    /// The byte ranges of the Avro input which don't match any pattern, as
+    /// handled by the [`Unmatched`](crate::avro::Unmatched) policy.
+    pub unmatched: Vec<Range<usize>>,
+}
+
+/// Removes the duplicate words from `suggestions` and orders them by
+/// descending score.
+///
+/// For duplicate words, the exact pattern match is kept over the other
+/// sources, and otherwise the first one. Suggestions with the same score are
+/// ordered lexicographically so that the result is deterministic.
+pub(crate) fn rank(suggestions: Vec<Suggestion>) -> Vec<Suggestion> {
+    let mut unique: HashMap<String, usize> = HashMap::new();
+    let mut ranked: Vec<Suggestion> = Vec::with_capacity(suggestions.len());
+
+    for suggestion in suggestions {
+        if let Some(&index) = unique.get(&suggestion.word) {
+            let existing = &mut ranked[index];
+
+            if existing.source != Source::Pattern && suggestion.source == Source::Pattern {
+                *existing = suggestion;
+            }
+        } else {
+            unique.insert(suggestion.word.clone(), ranked.len());
+            ranked.push(suggestion);
+        }
+    }
+
+    ranked.sort_by(compare);
+    ranked
+}
+
+/// Orders suggestions by descending score and then lexicographically.
+pub(crate) fn compare(a: &Suggestion, b: &Suggestion) -> Ordering {
+    b.score
+        .total_cmp(&a.score)
+        .then_with(|| a.word.cmp(&b.word))
+}
@@ -0,0 +1,272 @@
+use std::{collections::BTreeMap, fs, path::Path};
+
+use once_cell::sync::OnceCell;
+
+use crate::{
+    encoding,
+    fst::{FstTree, MAX_WEIGHT},
+    normalization::normalize,
+    Error,
+};
+
+/// A dictionary of the words added by the user, which are suggested along
+/// with the built-in words.
+///
+/// It is saved as a text file with a word on each line, optionally followed by
+/// a tab and its weight, just like the source word list of the built-in words.
+/// The words are normalized like the built-in words when they are added.
+///
+/// The FST of the words is rebuilt from all of them, in time linear in their
+/// number, the first time it is searched after the words changed. So adding
+/// or removing many words between two searches only rebuilds it once.
+#[derive(Clone)]
+pub struct UserDictionary {
+    words: BTreeMap<String, u64>,
+    /// The FST of the words, built on the first search after a change.
+    tree: OnceCell<FstTree<Vec<u8>>>,
+}
+
+impl UserDictionary {
+    /// Creates an empty user dictionary.
+    pub fn new() -> Self {
+        UserDictionary {
+            words: BTreeMap::new(),
+            tree: OnceCell::new(),
+        }
+    }
+
+    /// Loads the user dictionary saved in the file at `path`.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    /// Parses a user dictionary from the `text` of a saved file.
+    ///
+    /// Weights above [`u32::MAX`] are clamped to it, like those of the
+    /// built-in words.
+    pub fn parse(text: &str) -> Result<Self, Error> {
+        let mut words = BTreeMap::new();
+
+        for (index, line) in text.lines().enumerate() {
+            let (word, weight) = match line.split_once('\t') {
+                Some((word, weight)) => (
+                    word.trim(),
+                    weight
+                        .trim()
+                        .parse::<u64>()
+                        .map_err(|_| Error::InvalidUserDictionary { line: index + 1 })?,
+                ),
+                None => (line.trim(), 0),
+            };
+
+            let word = normalize(word);
+
+            if is_valid(&word) {
+                words.insert(word, weight.min(MAX_WEIGHT));
+            }
+        }
+
+        let mut dictionary = UserDictionary::new();
+        dictionary.words = words;
+
+        Ok(dictionary)
+    }
+
+    /// Saves the user dictionary to the file at `path`.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        Ok(fs::write(path, self.to_string())?)
+    }
+
+    /// Adds the `word` with a weight of `0`.
+    ///
+    /// Returns `false` if the word is empty, has unsupported characters or
+    /// was already present, in which case its weight is kept.
+    pub fn add(&mut self, word: &str) -> bool {
+        let word = normalize(word.trim());
+
+        if !is_valid(&word) || self.words.contains_key(&word) {
+            return false;
+        }
+
+        self.add_with_weight(&word, 0);
+        true
+    }
+
+    /// Adds the `word` with the given `weight`, replacing the weight if the
+    /// word was already present.
+    ///
+    /// Weights above [`u32::MAX`] are clamped to it, like those of the
+    /// built-in words. Empty words and words with unsupported characters are
+    /// ignored.
+    pub fn add_with_weight(&mut self, word: &str, weight: u64) {
+        let word = normalize(word.trim());
+
+        if !is_valid(&word) {
+            return;
+        }
+
+        self.words.insert(word, weight.min(MAX_WEIGHT));
+        self.changed();
+    }
+
+    /// Removes the `word`, returning whether it was present.
+    pub fn remove(&mut self, word: &str) -> bool {
+        let removed = self.words.remove(&normalize(word.trim())).is_some();
+
+        if removed {
+            self.changed();
+        }
+
+        removed
+    }
+
+    /// Returns whether the `word` is in the user dictionary.
+    pub fn contains(&self, word: &str) -> bool {
+        self.words.contains_key(&normalize(word))
+    }
+
+    /// Returns the weight of the `word`, if it is in the user dictionary.
+    pub fn weight(&self, word: &str) -> Option<u64> {
+        self.words.get(&normalize(word)).copied()
+    }
+
+    /// Returns the words and their weights in lexicographic order.
+    pub fn words(&self) -> impl Iterator<Item = (&str, u64)> {
+        self.words
+            .iter()
+            .map(|(word, &weight)| (word.as_str(), weight))
+    }
+
+    /// Returns the number of words in the user dictionary.
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    /// Returns whether the user dictionary is empty.
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// Returns the FST of the words, rebuilding it if they changed.
+    pub(crate) fn tree(&self) -> &FstTree<Vec<u8>> {
+        self.tree.get_or_init(|| FstTree::from_words(self.words()))
+    }
+
+    /// Drops the FST of the words, to rebuild it on the next search.
+    fn changed(&mut self) {
+        self.tree = OnceCell::new();
+    }
+}
+
+/// Whether the `word` can be stored in the user dictionary.
+fn is_valid(word: &str) -> bool {
+    !word.is_empty() && encoding::encode_str(word).is_some()
+}
+
+impl Default for UserDictionary {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Display for UserDictionary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (word, weight) in self.words() {
+            writeln!(f, "{word}\t{weight}")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_user_dictionary() {
+        let mut dictionary = UserDictionary::new();
+        assert!(dictionary.is_empty());
+
+        assert!(dictionary.add("গুগল"));
+        assert!(!dictionary.add("গুগল"));
+        assert!(!dictionary.add(" "));
+        assert!(!dictionary.add("गूगल"));
+        // The decomposed `ো` of `গুগোল`
+        assert!(dictionary.add("\u{0997}\u{09C1}\u{0997}\u{09C7}\u{09BE}\u{09B2}"));
+        assert!(dictionary.contains("\u{0997}\u{09C1}\u{0997}\u{09CB}\u{09B2}"));
+        assert!(dictionary.remove("\u{0997}\u{09C1}\u{0997}\u{09CB}\u{09B2}"));
+        dictionary.add_with_weight("ফেসবুক", 10);
+        dictionary.add_with_weight("গুগলিং", 5);
+
+        assert_eq!(dictionary.len(), 3);
+        assert!(dictionary.contains("গুগল"));
+        assert_eq!(dictionary.weight("ফেসবুক"), Some(10));
+
+        let node = dictionary.tree().matching_node("গুগল").unwrap();
+        assert_eq!(node.get_weighted_word(), Some(("গুগল".to_string(), 0)));
+
+        assert!(dictionary.remove("গুগল"));
+        assert!(!dictionary.remove("গুগল"));
+        assert!(!dictionary.contains("গুগল"));
+        assert!(dictionary
+            .tree()
+            .matching_node("গুগল")
+            .unwrap()
+            .get_word()
+            .is_none());
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let mut dictionary = UserDictionary::new();
+        dictionary.add("গুগল");
+        dictionary.add_with_weight("ফেসবুক", 10);
+
+        let text = dictionary.to_string();
+        assert_eq!(text, "গুগল\t0\nফেসবুক\t10\n");
+
+        let path = std::env::temp_dir().join(format!(
+            "upodesh-user-dictionary-test-{}.txt",
+            std::process::id()
+        ));
+        dictionary.save(&path).unwrap();
+        let loaded = UserDictionary::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(
+            loaded.words().collect::<Vec<_>>(),
+            [("গুগল", 0), ("ফেসবুক", 10)]
+        );
+
+        let parsed = UserDictionary::parse("টুইটার\n\nইউটিউব\t7\n").unwrap();
+        assert_eq!(
+            parsed.words().collect::<Vec<_>>(),
+            [("ইউটিউব", 7), ("টুইটার", 0)]
+        );
+
+        assert!(matches!(
+            UserDictionary::parse("টুইটার\t1\n\nইউটিউব\tmany\n"),
+            Err(Error::InvalidUserDictionary { line: 3 })
+        ));
+        assert!(matches!(UserDictionary::load(&path), Err(Error::Io(_))));
+    }
+
+    #[test]
+    fn test_weight_limit() {
+        let mut dictionary = UserDictionary::new();
+        dictionary.add_with_weight("গুগল", u64::MAX);
+        assert_eq!(dictionary.weight("গুগল"), Some(MAX_WEIGHT));
+
+        // The heaviest word of the FST too
+        let node = dictionary.tree().matching_node("গুগল").unwrap();
+        assert_eq!(node.best_weight(), MAX_WEIGHT);
+        assert_eq!(
+            node.get_weighted_word(),
+            Some(("গুগল".to_string(), MAX_WEIGHT))
+        );
+
+        let parsed = UserDictionary::parse(&format!("ফেসবুক\t{}\n", u64::MAX)).unwrap();
+        assert_eq!(parsed.weight("ফেসবুক"), Some(MAX_WEIGHT));
+    }
+}